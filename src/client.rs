@@ -0,0 +1,347 @@
+//! Call argument and response types for off-chain tools talking to the contract.
+//!
+//! Everything the contract methods return is re-exported from the crate root, so these types can't drift
+//! from what the contract actually serializes. The `*Args` structs mirror the method parameters by name.
+
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+
+pub use crate::errors::{DepositPurpose, QuizError};
+pub use crate::util::{
+    answer_commitment, choice_answer, first_char_hash, format_near, format_units, hash_answer,
+    normalize_answer, hash_with, parse_near, parse_units, salted_hash, signed_answer_message,
+};
+pub use crate::{
+    AccountStats, AccruedFees, ActivityDigest, AnswerFeedback, AttemptRecord, BatchSubmitResult,
+    ContractPhase, ContractStats, Difficulty, DifficultyMultipliers, FeedbackHints, HashAlgo,
+    JsonQuiz, JsonQuizDraft, JsonSeries, JsonStep, JsonVestingGrant, LeaderboardEntry, NftPrize,
+    NftToken, PayoutCap, PayoutPreference, PayoutRecord, PendingSubmission, PracticeResult,
+    PrizeSplit, PublishedQuizzes, QuizBudget, QuizFunding, QuizId, QuizInput, QuizKind,
+    QuizMetadata, QuizOptions, QuizPage, QuizStats, QuizStatus, QuizTransition, Referral,
+    ReferralConfig, ReferrerStats, ReviewStatus, SeasonPassConfig, SequenceStep, SeriesId,
+    ShutdownStatus, SolvedActivity, SplitShare, Sponsorship, StalePolicy, StatusChange,
+    StorageBalance, StorageBalanceBounds, Streak, StreakConfig, Submission, SubmitResult, TimeDecay,
+    TreasuryConfig, VestingPolicy, Wager, WagerStatus, Winnings,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CreateQuizArgs {
+    pub question: String,
+    pub correct_hashes: Vec<String>,
+    pub max_prize_amount: Option<U128>,
+    pub max_prize_near: Option<String>,
+    pub publish: bool,
+    pub options: Option<QuizOptions>,
+}
+
+impl CreateQuizArgs {
+    pub fn new(question: &str, answer: &str, max_prize_amount: u128) -> Self {
+        Self {
+            question: question.to_owned(),
+            correct_hashes: vec![hash_answer(answer)],
+            max_prize_amount: Some(U128(max_prize_amount)),
+            max_prize_near: None,
+            publish: false,
+            options: None,
+        }
+    }
+
+    pub fn salted(question: &str, answer: &str, salt: &str, max_prize_amount: u128) -> Self {
+        Self {
+            correct_hashes: vec![salted_hash(HashAlgo::Sha256, salt, answer)],
+            options: Some(QuizOptions { answer_salt: salt.to_owned(), ..Default::default() }),
+            ..Self::new(question, answer, max_prize_amount)
+        }
+    }
+
+    pub fn with_prize_near(question: &str, answer: &str, max_prize_near: &str) -> Self {
+        Self {
+            max_prize_amount: None,
+            max_prize_near: Some(max_prize_near.to_owned()),
+            ..Self::new(question, answer, 0)
+        }
+    }
+
+    // Token prizes are stored in the token's smallest unit; this takes a human amount such as "25.50" USDC.
+    pub fn with_prize_tokens(question: &str, answer: &str, token_id: AccountId, amount: &str, decimals: u8) -> Result<Self, String> {
+        let max_prize_amount = parse_units(amount, decimals as usize)?;
+        Ok(Self::new(question, answer, max_prize_amount).options(QuizOptions { prize_token: Some(token_id), ..Default::default() }))
+    }
+
+    pub fn also_accept(mut self, answer: &str) -> Self {
+        let (algo, salt) = match &self.options {
+            Some(options) => (options.hash_algo, options.answer_salt.as_str()),
+            None => (HashAlgo::Sha256, "")
+        };
+        self.correct_hashes.push(salted_hash(algo, salt, answer));
+        self
+    }
+
+    pub fn published(mut self) -> Self {
+        self.publish = true;
+        self
+    }
+
+    // Keeps the salt from `salted` unless the new options bring their own.
+    pub fn options(mut self, mut options: QuizOptions) -> Self {
+        if let Some(previous) = self.options.take().filter(|_| options.answer_salt.is_empty()) {
+            options.answer_salt = previous.answer_salt;
+        }
+        self.options = Some(options);
+        self
+    }
+}
+
+impl From<CreateQuizArgs> for QuizInput {
+    fn from(args: CreateQuizArgs) -> Self {
+        QuizInput {
+            question: args.question,
+            correct_hashes: args.correct_hashes,
+            max_prize_amount: args.max_prize_amount,
+            max_prize_near: args.max_prize_near,
+            publish: args.publish,
+            options: args.options,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CreateQuizzesArgs {
+    pub inputs: Vec<QuizInput>,
+}
+
+impl CreateQuizzesArgs {
+    pub fn new(quizzes: Vec<CreateQuizArgs>) -> Self {
+        Self { inputs: quizzes.into_iter().map(QuizInput::from).collect() }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SubmitAnswerArgs {
+    pub quiz_id: QuizId,
+    pub answer: String,
+    pub payout_to: Option<AccountId>,
+}
+
+impl SubmitAnswerArgs {
+    pub fn new(quiz_id: QuizId, answer: &str) -> Self {
+        Self { quiz_id, answer: answer.to_owned(), payout_to: None }
+    }
+
+    pub fn payout_to(mut self, payout_to: AccountId) -> Self {
+        self.payout_to = Some(payout_to);
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SubmitAnswersArgs {
+    pub quiz_id: QuizId,
+    pub answers: Vec<String>,
+    pub payout_to: Option<AccountId>,
+}
+
+impl SubmitAnswersArgs {
+    pub fn new(quiz_id: QuizId, answers: &[&str]) -> Self {
+        Self { quiz_id, answers: answers.iter().map(|answer| answer.to_string()).collect(), payout_to: None }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CommitAnswerArgs {
+    pub quiz_id: QuizId,
+    pub commitment: String,
+}
+
+impl CommitAnswerArgs {
+    pub fn new(account_id: &AccountId, quiz_id: QuizId, answer: &str, salt: &str) -> Self {
+        Self { quiz_id, commitment: answer_commitment(account_id, quiz_id, answer, salt) }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RevealAnswerArgs {
+    pub quiz_id: QuizId,
+    pub answer: String,
+    pub salt: String,
+    pub payout_to: Option<AccountId>,
+}
+
+impl RevealAnswerArgs {
+    pub fn new(quiz_id: QuizId, answer: &str, salt: &str) -> Self {
+        Self { quiz_id, answer: answer.to_owned(), salt: salt.to_owned(), payout_to: None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::serde::de::DeserializeOwned;
+    use near_sdk::serde_json;
+    use std::fmt::Debug;
+
+    fn round_trip<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: T) {
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(serde_json::from_str::<T>(&json).unwrap(), value);
+    }
+
+    // The entry points take named arguments rather than these structs, so their names are read off the source.
+    fn entry_point_params(method: &str) -> Vec<String> {
+        let source = include_str!("lib.rs");
+        let signature = source.split(&format!("pub fn {}(", method)).nth(1).unwrap_or_else(|| panic!("No entry point {}", method));
+        let mut params: Vec<String> = signature[..signature.find(')').unwrap()]
+            .split(", ")
+            .filter_map(|param| param.split_once(": ").map(|(name, _)| name.to_owned()))
+            .collect();
+        params.sort();
+        params
+    }
+
+    fn arg_names<T: Serialize>(args: &T) -> Vec<String> {
+        serde_json::to_value(args).unwrap().as_object().unwrap().keys().cloned().collect()
+    }
+
+    fn json_quiz() -> JsonQuiz {
+        JsonQuiz {
+            quiz_id: 3,
+            question: "What is the capital of France".to_owned(),
+            prize_amount: U128(1000),
+            status: QuizStatus::Published,
+            epoch_height: None,
+            stale: false,
+            global_attempts_remaining: Some(10),
+            guess_budget_exhausted: false,
+            remaining_placements: None,
+            expires_at: None,
+            expired: false,
+            category: None,
+            tags: vec![],
+            difficulty: Difficulty::Medium,
+            metadata: None,
+            prerequisites: vec![],
+            normalize_answers: false,
+            commit_reveal: false,
+            hash_algo: HashAlgo::Sha256,
+            max_retries: 3,
+            retry_price: None,
+            has_hint: false,
+            hint_penalty_bps: 0,
+            attempt_window_sec: None,
+            cooldown_sec: 0,
+            stake_required: None,
+            first_solver_bonus: None,
+            prize_token: None,
+            nft_prize: None,
+            prize_split: None,
+            pool_entry_fees: false,
+            entry_refund_rake_bps: None,
+            answer_parts: 1,
+            numeric_tolerance: None,
+            steps: None,
+            options: None,
+        }
+    }
+
+    #[test]
+    fn round_trip_args() {
+        round_trip(CreateQuizArgs::new("What is the capital of France", "Paris", 1000).also_accept("paris").published());
+        round_trip(CreateQuizArgs::with_prize_near("What is the capital of France", "Paris", "1.5").options(QuizOptions {
+            epoch_locked: true,
+            ..Default::default()
+        }));
+        round_trip(SubmitAnswerArgs::new(3, "Paris").payout_to(AccountId::new_unchecked("charity.near".to_owned())));
+        round_trip(SubmitAnswersArgs::new(3, &["Paris", "Berlin"]));
+        round_trip(CommitAnswerArgs::new(&AccountId::new_unchecked("alice.near".to_owned()), 3, "Paris", "pepper"));
+        round_trip(CreateQuizArgs::salted("What is the capital of France", "Paris", "f1c3", 1000).also_accept("paris"));
+        round_trip(RevealAnswerArgs::new(3, "Paris", "pepper"));
+    }
+
+    #[test]
+    fn args_match_entry_points() {
+        let alice = AccountId::new_unchecked("alice.near".to_owned());
+        assert_eq!(arg_names(&CreateQuizArgs::new("What is the capital of France", "Paris", 1000)), entry_point_params("create_quiz"));
+        assert_eq!(arg_names(&CreateQuizzesArgs::new(vec![])), entry_point_params("create_quizzes"));
+        assert_eq!(arg_names(&SubmitAnswerArgs::new(3, "Paris")), entry_point_params("submit_answer"));
+        assert_eq!(arg_names(&SubmitAnswersArgs::new(3, &["Paris"])), entry_point_params("submit_answers"));
+        assert_eq!(arg_names(&CommitAnswerArgs::new(&alice, 3, "Paris", "pepper")), entry_point_params("commit_answer"));
+        assert_eq!(arg_names(&RevealAnswerArgs::new(3, "Paris", "pepper")), entry_point_params("reveal_answer"));
+    }
+
+    #[test]
+    fn round_trip_responses() {
+        round_trip(json_quiz());
+        round_trip(PublishedQuizzes { quizzes: vec![json_quiz()], dangling_ids: vec![7], total: 2 });
+        round_trip(SubmitResult {
+            correct: true,
+            message: "Your answer is correct. You've got 1000 yoctoNEAR".to_owned(),
+            amount: Some(U128(1000)),
+            retries_left: 3,
+            feedback: None,
+            prize_pool_exhausted: false,
+        });
+        round_trip(SubmitResult {
+            correct: false,
+            message: "The answer is not right. You have 2 retries left".to_owned(),
+            amount: None,
+            retries_left: 2,
+            feedback: Some(AnswerFeedback { length_matches: true, first_char_matches: false }),
+            prize_pool_exhausted: false,
+        });
+        round_trip(JsonQuizDraft {
+            quiz_id: 3,
+            question: "What is the capital of France".to_owned(),
+            prize_amount: U128(1000),
+            status: QuizStatus::Unpublished,
+        });
+        round_trip(PayoutRecord {
+            quiz_id: 3,
+            account_id: AccountId::new_unchecked("alice.near".to_owned()),
+            recipient: AccountId::new_unchecked("alice.near".to_owned()),
+            attempt_number: 2,
+            base_prize: U128(1000),
+            modifiers_applied: vec![("retry_decay".to_owned(), -5000)],
+            final_amount: U128(500),
+            treasury_fee: U128(0),
+            token: Some(AccountId::new_unchecked("usdc.near".to_owned())),
+            block_height: 42,
+            round: 0,
+        });
+    }
+
+    #[test]
+    fn create_quiz_args_golden() {
+        let args = CreateQuizArgs::new("What is the capital of France", "Paris", 1000).published();
+        assert_eq!(
+            serde_json::to_string(&args).unwrap(),
+            r#"{"question":"What is the capital of France","correct_hashes":["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1"],"max_prize_amount":"1000","max_prize_near":null,"publish":true,"options":null}"#
+        );
+    }
+
+    #[test]
+    fn submit_result_golden() {
+        let result: SubmitResult = serde_json::from_str(
+            r#"{"correct":false,"message":"The answer is not right. You have 2 retries left","amount":null,"retries_left":2}"#,
+        )
+        .unwrap();
+        assert!(!result.correct);
+        assert_eq!(result.retries_left, 2);
+        assert_eq!(result.amount, None);
+    }
+
+    #[test]
+    fn prize_tokens_use_token_decimals() {
+        let usdc = AccountId::new_unchecked("usdc.near".to_owned());
+        let args = CreateQuizArgs::with_prize_tokens("What is the capital of France", "Paris", usdc.clone(), "25.50", 6).unwrap();
+        assert_eq!(args.max_prize_amount, Some(U128(25_500_000)));
+        assert_eq!(args.options.unwrap().prize_token, Some(usdc.clone()));
+        assert!(CreateQuizArgs::with_prize_tokens("What is the capital of France", "Paris", usdc.clone(), "0.0000001", 6).is_err());
+        assert!(CreateQuizArgs::with_prize_tokens("What is the capital of France", "Paris", usdc, "1", 39).is_err());
+    }
+}
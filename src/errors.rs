@@ -0,0 +1,211 @@
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::{self, Value};
+use near_sdk::AccountId;
+
+use crate::{ContractPhase, QuizId};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum DepositPurpose {
+    Attempt,
+    SeasonPass,
+    RetryInsurance,
+    ExtraRetry,
+    Escrow,
+    Stake,
+    Storage
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde", tag = "error", content = "data")]
+pub enum QuizError {
+    #[serde(rename = "ERR_NOT_OWNER")]
+    NotOwner,
+    #[serde(rename = "ERR_NOT_GRADER")]
+    NotGrader { account_id: AccountId },
+    #[serde(rename = "ERR_CONTRACT_PAUSED")]
+    ContractPaused,
+    #[serde(rename = "ERR_PHASE_NOT_ALLOWED")]
+    PhaseNotAllowed { phase: ContractPhase },
+    #[serde(rename = "ERR_QUIZ_NOT_FOUND")]
+    QuizNotFound { quiz_id: QuizId },
+    #[serde(rename = "ERR_QUIZ_DELETED")]
+    QuizDeleted { quiz_id: QuizId },
+    #[serde(rename = "ERR_QUIZ_NOT_PUBLISHED")]
+    QuizNotPublished { quiz_id: QuizId },
+    #[serde(rename = "ERR_STALE_EPOCH")]
+    StaleEpoch { quiz_id: QuizId, epoch_height: Option<u64> },
+    #[serde(rename = "ERR_QUIZ_EXPIRED")]
+    QuizExpired { quiz_id: QuizId, expires_at: u64 },
+    #[serde(rename = "ERR_PREREQUISITES_NOT_MET")]
+    PrerequisitesNotMet { quiz_id: QuizId, missing: Vec<QuizId> },
+    #[serde(rename = "ERR_COMMIT_REQUIRED")]
+    CommitRequired { quiz_id: QuizId },
+    #[serde(rename = "ERR_NO_COMMITMENT")]
+    NoCommitment { quiz_id: QuizId },
+    #[serde(rename = "ERR_REVEAL_TOO_EARLY")]
+    RevealTooEarly { quiz_id: QuizId, reveal_after: u64 },
+    #[serde(rename = "ERR_COMMITMENT_MISMATCH")]
+    CommitmentMismatch { quiz_id: QuizId },
+    #[serde(rename = "ERR_ATTEMPT_NOT_STARTED")]
+    AttemptNotStarted { quiz_id: QuizId },
+    #[serde(rename = "ERR_ALREADY_SOLVED")]
+    AlreadySolved { quiz_id: QuizId },
+    #[serde(rename = "ERR_OUT_OF_RETRIES")]
+    OutOfRetries { quiz_id: QuizId, refresh_at: Option<u64> },
+    #[serde(rename = "ERR_REVIEW_PENDING")]
+    ReviewPending { quiz_id: QuizId },
+    #[serde(rename = "ERR_ATTEMPT_COOLDOWN")]
+    AttemptCooldown { quiz_id: QuizId, retry_at: u64 },
+    #[serde(rename = "ERR_PLACEMENTS_EXHAUSTED")]
+    PlacementsExhausted { quiz_id: QuizId },
+    #[serde(rename = "ERR_GUESS_BUDGET_EXHAUSTED")]
+    GuessBudgetExhausted { quiz_id: QuizId },
+    #[serde(rename = "ERR_INSUFFICIENT_DEPOSIT")]
+    InsufficientDeposit { purpose: DepositPurpose, required: U128, attached: U128 },
+    #[serde(rename = "ERR_PAYOUT_TO_CONTRACT")]
+    PayoutToContract,
+    #[serde(rename = "ERR_SUBMITTER_NOT_APPROVED")]
+    SubmitterNotApproved { submitter: AccountId, beneficiary: AccountId },
+    #[serde(rename = "ERR_SIGNING_KEY_NOT_REGISTERED")]
+    SigningKeyNotRegistered { account_id: AccountId },
+    #[serde(rename = "ERR_UNSUPPORTED_KEY_TYPE")]
+    UnsupportedKeyType,
+    #[serde(rename = "ERR_NONCE_REUSED")]
+    NonceReused { nonce: u64, last_nonce: u64 },
+    #[serde(rename = "ERR_INVALID_SIGNATURE")]
+    InvalidSignature,
+    #[serde(rename = "ERR_TREASURY_FEE_TOO_HIGH")]
+    TreasuryFeeTooHigh { fee_bps: u16, max_bps: u16 },
+    #[serde(rename = "ERR_NOTHING_TO_CLAIM")]
+    NothingToClaim,
+    #[serde(rename = "ERR_NOTHING_TO_WITHDRAW")]
+    NothingToWithdraw,
+    #[serde(rename = "ERR_ESCROW_LOCKED")]
+    EscrowLocked { quiz_id: QuizId },
+    #[serde(rename = "ERR_NOTHING_TO_REFUND")]
+    NothingToRefund,
+    #[serde(rename = "ERR_SPLIT_PENDING")]
+    SplitPending { quiz_id: QuizId },
+    #[serde(rename = "ERR_STAKE_REQUIRED")]
+    StakeRequired { quiz_id: QuizId, required: U128 },
+    #[serde(rename = "ERR_STORAGE_NOT_REGISTERED")]
+    StorageNotRegistered { account_id: AccountId },
+    #[serde(rename = "ERR_WRONG_ANSWER_COUNT")]
+    WrongAnswerCount { quiz_id: QuizId, expected: usize }
+}
+
+impl QuizError {
+    pub fn message(&self) -> String {
+        match self {
+            QuizError::NotOwner => "This method can only be called by owner".to_owned(),
+            QuizError::NotGrader { account_id } => format!("{} cannot grade submissions", account_id),
+            QuizError::ContractPaused => "The contract is paused".to_owned(),
+            QuizError::PhaseNotAllowed { phase } => format!("Not allowed while the contract is {:?}", phase),
+            QuizError::QuizNotFound { .. } => "No such quiz found".to_owned(),
+            QuizError::QuizDeleted { .. } => "This quiz was deleted".to_owned(),
+            QuizError::QuizNotPublished { .. } => "Cannot submit an answer to unpublished quiz".to_owned(),
+            QuizError::StaleEpoch { .. } => "This quiz was for a previous epoch".to_owned(),
+            QuizError::QuizExpired { .. } => "This quiz has expired".to_owned(),
+            QuizError::PrerequisitesNotMet { missing, .. } => format!("Solve quizzes {:?} first", missing),
+            QuizError::CommitRequired { .. } => "This quiz only takes committed answers, use commit_answer and reveal_answer".to_owned(),
+            QuizError::NoCommitment { .. } => "Commit to an answer before revealing it".to_owned(),
+            QuizError::RevealTooEarly { reveal_after, .. } => format!("The answer can be revealed from block {}", reveal_after),
+            QuizError::CommitmentMismatch { .. } => "The answer and salt do not match the commitment".to_owned(),
+            QuizError::AttemptNotStarted { .. } => "Call start_attempt before answering this quiz".to_owned(),
+            QuizError::AlreadySolved { .. } => "This quiz is already solved by you".to_owned(),
+            QuizError::OutOfRetries { .. } => "You can no longer solve this quiz. You are out of tries.".to_owned(),
+            QuizError::ReviewPending { .. } => "Your previous answer is still waiting for review".to_owned(),
+            QuizError::AttemptCooldown { retry_at, .. } => format!("Wait until {} before trying this quiz again", retry_at),
+            QuizError::PlacementsExhausted { .. } => "All placements on this quiz have been claimed".to_owned(),
+            QuizError::GuessBudgetExhausted { .. } => "guess budget exhausted".to_owned(),
+            QuizError::InsufficientDeposit { purpose, required, .. } => match purpose {
+                DepositPurpose::Attempt => format!("This attempt costs {} yoctoNEAR", required.0),
+                DepositPurpose::SeasonPass => format!("Attach at least {} yoctoNEAR to buy a season pass", required.0),
+                DepositPurpose::RetryInsurance => format!("Retry insurance costs {} yoctoNEAR", required.0),
+                DepositPurpose::ExtraRetry => format!("An extra retry costs {} yoctoNEAR", required.0),
+                DepositPurpose::Escrow => format!("Attach {} yoctoNEAR to fund the quiz prizes", required.0),
+                DepositPurpose::Stake => format!("This quiz needs a stake of {} yoctoNEAR", required.0),
+                DepositPurpose::Storage => format!("Registering costs a storage deposit of {} yoctoNEAR", required.0)
+            },
+            QuizError::PayoutToContract => "Cannot pay a prize out to the quiz contract itself".to_owned(),
+            QuizError::SubmitterNotApproved { submitter, beneficiary } => {
+                format!("{} is not approved to submit answers for {}", submitter, beneficiary)
+            }
+            QuizError::SigningKeyNotRegistered { account_id } => format!("No signing key registered for {}", account_id),
+            QuizError::UnsupportedKeyType => "Only ed25519 signing keys are supported".to_owned(),
+            QuizError::NonceReused { nonce, .. } => format!("Nonce {} was already used", nonce),
+            QuizError::InvalidSignature => "Invalid signature".to_owned(),
+            QuizError::TreasuryFeeTooHigh { max_bps, .. } => format!("Treasury fee cannot exceed {} bps", max_bps),
+            QuizError::NothingToClaim => "Nothing to claim".to_owned(),
+            QuizError::NothingToWithdraw => "Nothing to withdraw".to_owned(),
+            QuizError::EscrowLocked { .. } => "Escrow can only be refunded once the quiz is unpublished, closed or expired".to_owned(),
+            QuizError::NothingToRefund => "Nothing to refund".to_owned(),
+            QuizError::SplitPending { .. } => "The prize is split once the quiz closes or all winners are in".to_owned(),
+            QuizError::StakeRequired { required, .. } => format!("Stake {} yoctoNEAR with stake_for_quiz before answering", required.0),
+            QuizError::StorageNotRegistered { account_id } => format!("{} has to register with storage_deposit first", account_id),
+            QuizError::WrongAnswerCount { expected: 1, .. } => "This quiz takes a single answer".to_owned(),
+            QuizError::WrongAnswerCount { expected, .. } => format!("This quiz takes {} answers", expected)
+        }
+    }
+
+    // The code comes first so explorers and plain substring matches keep working; the JSON follows on the same line.
+    pub fn to_panic_string(&self) -> String {
+        let mut payload = serde_json::to_value(self).unwrap();
+        payload["message"] = Value::String(self.message());
+
+        format!("{} {}", payload["error"].as_str().unwrap(), payload)
+    }
+
+    pub fn from_panic_string(panic: &str) -> Option<QuizError> {
+        let (_, payload) = panic.split_once(' ')?;
+        let mut payload: Value = serde_json::from_str(payload).ok()?;
+        payload.as_object_mut()?.remove("message");
+
+        serde_json::from_value(payload).ok()
+    }
+}
+
+pub(crate) fn panic_json(err: QuizError) -> ! {
+    panic!("{}", err.to_panic_string())
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    fn round_trip(err: QuizError) {
+        assert_eq!(QuizError::from_panic_string(&err.to_panic_string()), Some(err));
+    }
+
+    #[test]
+    fn panic_string_starts_with_code() {
+        let panic = QuizError::OutOfRetries { quiz_id: 3, refresh_at: None }.to_panic_string();
+        assert_eq!(
+            panic,
+            r#"ERR_OUT_OF_RETRIES {"data":{"quiz_id":3,"refresh_at":null},"error":"ERR_OUT_OF_RETRIES","message":"You can no longer solve this quiz. You are out of tries."}"#
+        );
+    }
+
+    #[test]
+    fn round_trip_variants() {
+        round_trip(QuizError::OutOfRetries { quiz_id: 3, refresh_at: Some(1_000) });
+        round_trip(QuizError::InsufficientDeposit { purpose: DepositPurpose::Attempt, required: U128(10), attached: U128(1) });
+        round_trip(QuizError::NotOwner);
+        round_trip(QuizError::QuizDeleted { quiz_id: 7 });
+        round_trip(QuizError::PhaseNotAllowed { phase: ContractPhase::Closed });
+        round_trip(QuizError::SubmitterNotApproved {
+            submitter: AccountId::new_unchecked("dao.near".to_owned()),
+            beneficiary: AccountId::new_unchecked("alice.near".to_owned())
+        });
+        round_trip(QuizError::NonceReused { nonce: 1, last_nonce: 4 });
+        round_trip(QuizError::RevealTooEarly { quiz_id: 2, reveal_after: 12 });
+        round_trip(QuizError::WrongAnswerCount { quiz_id: 5, expected: 3 });
+    }
+
+    #[test]
+    fn rejects_plain_panics() {
+        assert_eq!(QuizError::from_panic_string("duplicate question (see quiz 0)"), None);
+    }
+}
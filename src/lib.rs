@@ -1,357 +1,8428 @@
 use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{LookupMap, UnorderedSet};
-use near_sdk::{near_bindgen, AccountId, PanicOnDefault, env, BorshStorageKey, Promise};
+use near_sdk::collections::{LookupMap, UnorderedMap, UnorderedSet, Vector};
+use near_sdk::json_types::{Base64VecU8, U128};
+use near_sdk::{near_bindgen, AccountId, PanicOnDefault, env, BorshStorageKey, Gas, Promise, PromiseOrValue, PromiseResult, PublicKey, CurveType};
 use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::json;
 use sha2::{Sha256, Digest};
+use std::collections::HashMap;
 
-type QuizId = u64;
+use crate::errors::{panic_json, DepositPurpose, QuizError};
+use crate::events::emit_event;
+use crate::payout::{apply_bps, boosted_prize, compute_payout, decay_bps, PayoutBreakdown, PayoutContext, PayoutParams, BASIS_POINTS};
+
+#[cfg(all(any(feature = "client", test), not(target_arch = "wasm32")))]
+pub mod client;
+pub mod errors;
+mod events;
+mod migrate;
+mod payout;
+pub mod util;
+
+pub type QuizId = u64;
+pub type SeriesId = u64;
+
+const DEFAULT_RETRIES: usize = 3;
+const MAX_RETRIES: usize = u8::MAX as usize;
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+const MAX_TREASURY_FEE_BPS: u16 = 1_000;
+const DEFAULT_ACTIVITY_RETENTION: u64 = 100;
+const WAGER_WINDOW_BLOCKS: u64 = 600;
+const DELETE_CLEANUP_LIMIT: u64 = 100;
+const MIN_REVEAL_DELAY_BLOCKS: u64 = 2;
+const MAX_BATCH_SUBMISSIONS: usize = 20;
+const MAX_SPONSOR_MESSAGE_LEN: usize = 280;
+const LEADERBOARD_SIZE: usize = 100;
+// Flat NEP-145 deposit, about 1 KB of per-account state at the current storage price.
+const STORAGE_BALANCE: u128 = 10_000_000_000_000_000_000;
+const GAS_FOR_FT_TRANSFER: Gas = Gas(10_000_000_000_000);
+const GAS_FOR_FT_CALLBACK: Gas = Gas(10_000_000_000_000);
+const GAS_FOR_NFT_TRANSFER: Gas = Gas(15_000_000_000_000);
+const GAS_FOR_NFT_CALLBACK: Gas = Gas(10_000_000_000_000);
+const GAS_FOR_PRIZE_CALLBACK: Gas = Gas(5_000_000_000_000);
+
+const ACTIVE_ONLY: &[ContractPhase] = &[ContractPhase::Active];
+const ACCEPTING_ANSWERS: &[ContractPhase] = &[ContractPhase::Active, ContractPhase::ShuttingDown];
+const ANY_PHASE: &[ContractPhase] = &[ContractPhase::Active, ContractPhase::ShuttingDown, ContractPhase::Closed];
 
 #[derive(BorshSerialize, BorshStorageKey)]
 enum StorageKey {
     Quizzes,
     PublishedQuizzes,
     SolvedQuizzes,
-    RetriesLeft
+    RetriesLeft,
+    DraftReviewers,
+    DeletedQuizzes,
+    ApprovedSubmitters,
+    QuestionIndex,
+    PayoutRecords,
+    AccountPayouts,
+    SeasonPasses,
+    PayoutPreferences,
+    ClaimablePrizes,
+    SigningKeys,
+    SigningNonces,
+    ActivityLog,
+    Wagers,
+    PendingWagers,
+    QuizParticipants,
+    CategoryQuizzes,
+    CategoryNames,
+    Series,
+    SeriesQuizzes,
+    QuizHistory,
+    AnswerCommitments,
+    AttemptStarts,
+    AttemptHistory,
+    Submissions,
+    ReviewQueues,
+    Graders,
+    QuizFunders,
+    ClaimableTokens,
+    SplitWinners,
+    Streaks,
+    AccruedTokenFees,
+    Winnings,
+    QuizSponsors,
+    Vesting,
+    Stakes,
+    StorageDeposits,
+    EntryFees,
+    Referrals,
+    Referrers,
+    AccountTallies,
+    RetryRecords
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum QuizStatus {
+    Published,
+    Unpublished,
+    Archived,
+    Closed
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ContractPhase {
+    Active,
+    ShuttingDown,
+    Closed
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ShutdownStatus {
+    pub phase: ContractPhase,
+    pub closes_at: Option<u64>
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PublishedQuizzes {
+    pub quizzes: Vec<JsonQuiz>,
+    pub dangling_ids: Vec<QuizId>,
+    pub total: u64
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JsonQuiz {
+    pub quiz_id: QuizId,
+    pub question: String,
+    pub prize_amount: U128,
+    pub status: QuizStatus,
+    pub epoch_height: Option<u64>,
+    pub stale: bool,
+    pub global_attempts_remaining: Option<u64>,
+    pub guess_budget_exhausted: bool,
+    pub remaining_placements: Option<Vec<U128>>,
+    pub expires_at: Option<u64>,
+    pub expired: bool,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub difficulty: Difficulty,
+    pub metadata: Option<QuizMetadata>,
+    pub prerequisites: Vec<QuizId>,
+    pub normalize_answers: bool,
+    pub commit_reveal: bool,
+    pub hash_algo: HashAlgo,
+    pub max_retries: usize,
+    pub retry_price: Option<U128>,
+    pub has_hint: bool,
+    pub hint_penalty_bps: u32,
+    pub attempt_window_sec: Option<u64>,
+    pub cooldown_sec: u64,
+    pub stake_required: Option<U128>,
+    pub first_solver_bonus: Option<U128>,
+    pub prize_token: Option<AccountId>,
+    pub nft_prize: Option<NftToken>,
+    pub prize_split: Option<PrizeSplit>,
+    pub pool_entry_fees: bool,
+    pub entry_refund_rake_bps: Option<u32>,
+    pub answer_parts: usize,
+    pub numeric_tolerance: Option<u64>,
+    pub steps: Option<usize>,
+    pub options: Option<Vec<String>>
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum QuizTransition {
+    Created,
+    Published,
+    Reopened,
+    Unpublished,
+    Archived,
+    Closed
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StatusChange {
+    pub transition: QuizTransition,
+    pub timestamp: u64,
+    pub account_id: AccountId
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JsonSeries {
+    pub series_id: SeriesId,
+    pub name: String,
+    pub quiz_count: u64
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JsonQuizDraft {
+    pub quiz_id: QuizId,
+    pub question: String,
+    pub prize_amount: U128,
+    pub status: QuizStatus
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct QuizPage {
+    pub quiz_id: QuizId,
+    pub question: String,
+    pub prize_amount: U128,
+    pub status: QuizStatus,
+    pub epoch_height: Option<u64>,
+    pub stale: bool,
+    pub solver_count: u64,
+    pub practice_attempts: u64,
+    pub global_attempts_remaining: Option<u64>,
+    pub retries_left: Option<usize>,
+    pub solved: Option<bool>,
+    pub time_remaining_sec: Option<u64>,
+    pub eligible: Option<bool>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SubmitResult {
+    pub correct: bool,
+    pub message: String,
+    pub amount: Option<U128>,
+    pub retries_left: usize,
+    pub feedback: Option<AnswerFeedback>,
+    #[serde(default)]
+    pub prize_pool_exhausted: bool
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BatchSubmitResult {
+    pub quiz_id: QuizId,
+    pub result: Option<SubmitResult>,
+    pub error: Option<QuizError>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AnswerFeedback {
+    pub length_matches: bool,
+    pub first_char_matches: bool
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum HashAlgo {
+    #[default]
+    Sha256,
+    Keccak256,
+    // BLAKE2b with a 256-bit digest, so every algorithm yields 64 hex characters.
+    Blake2b
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum QuizKind {
+    #[default]
+    FreeText,
+    // Option count and salt are both public, so the correct hash gives away its option index in
+    // a handful of tries. Treat the answer as public; commit_reveal only hides other players' picks.
+    MultipleChoice { options: Vec<String>, salt: String },
+    MultiPart { part_hashes: Vec<String> },
+    // The target sits in contract state in the clear, so anyone reading storage has the answer.
+    // Use it for estimation rounds, not for prizes worth the lookup.
+    Numeric { target: i64, tolerance: u64 },
+    Sequence { steps: Vec<SequenceStep> },
+    ManualReview
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ReviewStatus {
+    Pending,
+    Accepted,
+    Rejected { reason: Option<String> }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Submission {
+    pub round: u32,
+    pub answer: String,
+    pub submitted_at: u64,
+    pub payout_to: Option<AccountId>,
+    pub status: ReviewStatus
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct QuizFunding {
+    pub balance: U128,
+    pub total_funded: U128,
+    pub funders: u64
+}
+
+// near_distributed counts NEAR prizes and first-solver bonuses, including amounts still vesting or waiting to be claimed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContractStats {
+    pub total_quizzes: u64,
+    pub published_quizzes: u64,
+    pub total_attempts: u64,
+    pub total_solves: u64,
+    pub unique_solvers: u64,
+    pub near_distributed: U128
+}
+
+// participants counts every account that interacted with the quiz, including hint and insurance buyers who never answered.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct QuizStats {
+    pub attempts: u64,
+    pub practice_attempts: u64,
+    pub participants: u64,
+    pub solver_count: u64,
+    pub winner_count: u64,
+    pub paid_out: U128
+}
+
+// funded is everything put towards prizes and not refunded; pending is pool already owed to split winners awaiting distribution.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct QuizBudget {
+    pub token: Option<AccountId>,
+    pub funded: U128,
+    pub paid_out: U128,
+    pub pending_claims: U128,
+    pub remaining: U128
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PendingSubmission {
+    pub account_id: AccountId,
+    pub answer: String,
+    pub submitted_at: u64
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SequenceStep {
+    pub question: String,
+    pub correct_hashes: Vec<String>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JsonStep {
+    pub step: usize,
+    pub steps: usize,
+    pub question: String
+}
+
+// Split quizzes share max_prize_amount among up to max_winners solvers once the quiz closes.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum PrizeSplit {
+    Equal,
+    ByAttempts
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SplitShare {
+    pub account_id: AccountId,
+    pub recipient: AccountId,
+    pub attempt_number: u8
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftToken {
+    pub contract_id: AccountId,
+    pub token_id: String
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftPrize {
+    pub token: NftToken,
+    pub deposited_by: Option<AccountId>,
+    pub winner: Option<AccountId>,
+    pub delivered: bool
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FeedbackHints {
+    pub length: u16,
+    pub first_char_hash: String
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PracticeResult {
+    pub correct: bool
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AttemptRecord {
+    pub round: u32,
+    pub correct: bool,
+    pub timestamp: u64
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PayoutRecord {
+    pub quiz_id: QuizId,
+    pub account_id: AccountId,
+    pub recipient: AccountId,
+    pub attempt_number: u8,
+    pub base_prize: U128,
+    pub modifiers_applied: Vec<(String, i64)>,
+    pub final_amount: U128,
+    pub treasury_fee: U128,
+    pub token: Option<AccountId>,
+    pub block_height: u64,
+    pub round: u32
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TreasuryConfig {
+    pub account_id: AccountId,
+    pub fee_bps: u16
+}
+
+// Caps what a single account can win in NEAR, overall or within the current season.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PayoutCap {
+    pub amount: U128,
+    pub per_season: bool
+}
+
+// Ranked by solved count, then by NEAR winnings.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LeaderboardEntry {
+    pub account_id: AccountId,
+    pub solved: u32,
+    pub winnings: U128
+}
+
+// rank is only known within the top LEADERBOARD_SIZE accounts.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccountStats {
+    pub solved: u32,
+    pub attempts: u64,
+    pub winnings: U128,
+    pub streak: Streak,
+    pub rank: Option<u32>
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+struct AccountTally {
+    solved: u32,
+    attempts: u64
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+struct AccountWinnings {
+    total: u128,
+    season: u32,
+    season_total: u128
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Winnings {
+    pub total: U128,
+    pub season_total: U128,
+    pub remaining: Option<U128>
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Sponsorship {
+    pub account_id: AccountId,
+    pub amount: U128,
+    pub message: Option<String>,
+    pub timestamp: u64
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalance {
+    pub total: U128,
+    pub available: U128
 }
 
-#[derive(BorshDeserialize, BorshSerialize, Deserialize, Serialize, Debug, PartialEq)]
-#[serde(crate = "near_sdk::serde")]
-pub enum QuizStatus {
-    Published,
-    Unpublished
-}
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalanceBounds {
+    pub min: U128,
+    pub max: Option<U128>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AccruedFees {
+    pub near: U128,
+    pub tokens: Vec<(AccountId, U128)>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PayoutPreference {
+    pub min_transfer: U128,
+    pub accrued: U128
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SeasonPassConfig {
+    pub price: U128,
+    pub duration_sec: u64,
+    pub season_ends_at: Option<u64>
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SolvedActivity {
+    pub quiz_id: QuizId,
+    pub account_id: AccountId,
+    pub amount: U128
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ActivityDigest {
+    pub published: Vec<QuizId>,
+    pub solved: Vec<SolvedActivity>,
+    pub expired: Vec<QuizId>,
+    pub latest_block_height: u64
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum WagerStatus {
+    Pending,
+    Won,
+    Forfeited,
+    Refunded
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Wager {
+    pub follow_up_quiz_id: QuizId,
+    pub amount: U128,
+    pub status: WagerStatus
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+pub enum ActivityKind {
+    Published,
+    Solved { account_id: AccountId, amount: u128 },
+    Expired
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+pub struct ActivityEntry {
+    block_height: u64,
+    quiz_id: QuizId,
+    kind: ActivityKind
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+pub struct SeasonPass {
+    season: u32,
+    expires_at: u64
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(crate = "near_sdk::serde", default)]
+pub struct QuizMetadata {
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub reference: Option<String>
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Medium,
+    Hard
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DifficultyMultipliers {
+    pub easy_bps: u32,
+    pub medium_bps: u32,
+    pub hard_bps: u32
+}
+
+impl Default for DifficultyMultipliers {
+    fn default() -> Self {
+        let neutral = BASIS_POINTS as u32;
+        Self { easy_bps: neutral, medium_bps: neutral, hard_bps: neutral }
+    }
+}
+
+impl DifficultyMultipliers {
+    fn bps(&self, difficulty: Difficulty) -> u32 {
+        match difficulty {
+            Difficulty::Easy => self.easy_bps,
+            Difficulty::Medium => self.medium_bps,
+            Difficulty::Hard => self.hard_bps
+        }
+    }
+}
+
+// Each solve in an unbroken run adds bonus_bps_per_solve to the next payout, up to max_bonus_bps.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StreakConfig {
+    pub bonus_bps_per_solve: u32,
+    pub max_bonus_bps: u32
+}
+
+impl StreakConfig {
+    fn bps(&self, streak: u32) -> u32 {
+        BASIS_POINTS as u32 + self.bonus_bps_per_solve.saturating_mul(streak).min(self.max_bonus_bps)
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Streak {
+    pub current: u32,
+    pub best: u32
+}
+
+// The referrer of an account earns bonus_bps of each of its first rewarded_wins NEAR prizes, paid out of accrued treasury fees.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReferralConfig {
+    pub bonus_bps: u32,
+    pub rewarded_wins: u32
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Referral {
+    pub referrer: AccountId,
+    pub rewarded_wins: u32
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReferrerStats {
+    pub referred: u32,
+    pub earned: U128
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StalePolicy {
+    pub after_sec: u64,
+    pub prize_increase_bps: u32,
+    pub max_increases: u8
+}
+
+// Prizes of at least threshold unlock in equal tranches, one every interval_sec after the payout.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VestingPolicy {
+    pub threshold: U128,
+    pub tranches: u8,
+    pub interval_sec: u64
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+struct VestingGrant {
+    quiz_id: QuizId,
+    total: u128,
+    claimed: u128,
+    starts_at: u64,
+    tranches: u8,
+    interval_sec: u64
+}
+
+impl VestingGrant {
+    fn unlocked(&self) -> u128 {
+        let elapsed_sec = env::block_timestamp().saturating_sub(self.starts_at) / NANOS_PER_SEC;
+        let tranches = (elapsed_sec / self.interval_sec).min(self.tranches as u64) as u128;
+        if tranches == self.tranches as u128 {
+            return self.total;
+        }
+        self.total / self.tranches as u128 * tranches
+    }
+
+    fn next_unlock_at(&self) -> Option<u64> {
+        let interval = self.interval_sec * NANOS_PER_SEC;
+        let passed = env::block_timestamp().saturating_sub(self.starts_at) / interval;
+        (passed < self.tranches as u64).then(|| self.starts_at + (passed + 1) * interval)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JsonVestingGrant {
+    pub quiz_id: QuizId,
+    pub total: U128,
+    pub claimed: U128,
+    pub unlocked: U128,
+    pub next_unlock_at: Option<u64>
+}
+
+// The prize falls from full at publish time to floor_bps at the deadline, continuously or every step_sec.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TimeDecay {
+    pub floor_bps: u32,
+    pub step_sec: Option<u64>
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde", default)]
+pub struct QuizOptions {
+    pub epoch_locked: bool,
+    pub question_parts: Vec<String>,
+    pub reveal_interval_sec: u64,
+    pub stage_payout_bps: Vec<u32>,
+    pub payout_schedule: Vec<u32>,
+    pub allow_duplicate: bool,
+    pub global_attempt_cap: Option<u64>,
+    pub fee_schedule: Vec<U128>,
+    pub practice_disabled: bool,
+    pub stale_policy: Option<StalePolicy>,
+    pub time_decay: Option<TimeDecay>,
+    pub vesting: Option<VestingPolicy>,
+    pub feedback_hashes: Option<FeedbackHints>,
+    pub insurance_price: Option<U128>,
+    pub placements: Vec<U128>,
+    pub expires_at: Option<u64>,
+    pub publish_at: Option<u64>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub difficulty: Difficulty,
+    pub max_winners: Option<u64>,
+    pub metadata: Option<QuizMetadata>,
+    pub prerequisites: Vec<QuizId>,
+    pub normalize_answers: bool,
+    pub commit_reveal: bool,
+    pub answer_salt: String,
+    pub hash_algo: HashAlgo,
+    pub max_retries: Option<usize>,
+    pub retry_price: Option<U128>,
+    pub hint: Option<String>,
+    pub hint_penalty_bps: u32,
+    pub attempt_window_sec: Option<u64>,
+    pub cooldown_sec: u64,
+    pub stake_required: Option<U128>,
+    pub first_solver_bonus: Option<U128>,
+    pub prize_token: Option<AccountId>,
+    pub nft_prize: Option<NftToken>,
+    pub prize_split: Option<PrizeSplit>,
+    pub pool_entry_fees: bool,
+    pub entry_refund_rake_bps: Option<u32>,
+    pub kind: QuizKind
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct QuizInput {
+    pub question: String,
+    pub correct_hashes: Vec<String>,
+    pub max_prize_amount: Option<U128>,
+    pub max_prize_near: Option<String>,
+    pub publish: bool,
+    pub options: Option<QuizOptions>
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct Quiz {
+    status: QuizStatus,
+    question: String,
+    correct_hashes: Vec<String>,
+    max_prize_amount: u128,
+    epoch_locked: bool,
+    epoch_height: Option<u64>,
+    round: u32,
+    question_parts: Vec<String>,
+    reveal_interval_sec: u64,
+    stage_payout_bps: Vec<u32>,
+    payout_schedule: Vec<u32>,
+    published_at: Option<u64>,
+    solver_count: u64,
+    global_attempt_cap: Option<u64>,
+    total_attempts: u64,
+    // Unlike total_attempts this survives reopen_quiz, so update_quiz stays locked once anyone has played.
+    ever_attempted: bool,
+    fee_schedule: Vec<u128>,
+    practice_disabled: bool,
+    practice_attempts: u64,
+    stale_policy: Option<StalePolicy>,
+    time_decay: Option<TimeDecay>,
+    vesting: Option<VestingPolicy>,
+    first_solved_at: Option<u64>,
+    feedback_hashes: Option<FeedbackHints>,
+    insurance_price: Option<u128>,
+    placements: Vec<u128>,
+    expires_at: Option<u64>,
+    category: Option<String>,
+    tags: Vec<String>,
+    difficulty: Difficulty,
+    previous_hashes: Vec<String>,
+    max_winners: Option<u64>,
+    winner_count: u64,
+    metadata: Option<QuizMetadata>,
+    prerequisites: Vec<QuizId>,
+    normalize_answers: bool,
+    commit_reveal: bool,
+    answer_salt: String,
+    hash_algo: HashAlgo,
+    max_retries: usize,
+    retry_price: Option<u128>,
+    hint: Option<String>,
+    hint_penalty_bps: u32,
+    attempt_window_sec: Option<u64>,
+    cooldown_sec: u64,
+    stake_required: Option<u128>,
+    first_solver_bonus: Option<u128>,
+    first_solver: Option<AccountId>,
+    prize_token: Option<AccountId>,
+    nft_prize: Option<NftPrize>,
+    prize_split: Option<PrizeSplit>,
+    pool_entry_fees: bool,
+    entry_refund_rake_bps: Option<u32>,
+    escrow: u128,
+    funded: u128,
+    paid_out: u128,
+    // Stakes and refundable entry fees, which stay the players' until they take them back.
+    deposits_held: u128,
+    kind: QuizKind
+}
+
+impl Quiz {
+    fn revealed_parts(&self) -> usize {
+        let total = self.question_parts.len();
+        match self.published_at {
+            Some(published_at) if self.reveal_interval_sec > 0 => {
+                let elapsed = env::block_timestamp().saturating_sub(published_at);
+                let stages = elapsed / (self.reveal_interval_sec * NANOS_PER_SEC);
+                total.min(stages as usize + 1)
+            }
+            _ => total
+        }
+    }
+
+    fn visible_question(&self) -> String {
+        if self.question_parts.is_empty() {
+            return self.question.clone();
+        }
+
+        self.question_parts[..self.revealed_parts()].join(" ")
+    }
+
+    fn next_placement(&self) -> Option<u64> {
+        (!self.placements.is_empty()).then_some(self.solver_count)
+    }
+
+    fn placements_exhausted(&self) -> bool {
+        !self.placements.is_empty() && self.solver_count as usize >= self.placements.len()
+    }
+
+    fn remaining_placements(&self) -> Option<Vec<U128>> {
+        self.next_placement().map(|placement| self.placements.iter().skip(placement as usize).map(|&prize| U128(prize)).collect())
+    }
+
+    fn base_prize(&self, placement: Option<u64>) -> u128 {
+        placement.map_or(self.max_prize_amount, |placement| self.placements.get(placement as usize).copied().unwrap_or(0))
+    }
+
+    // Quizzes without a winner cap are funded for a single winner; later solvers get what is left.
+    // Token prizes are funded with ft_transfer_call after creation, so they need no NEAR up front.
+    fn near_budget(&self) -> u128 {
+        if self.prize_token.is_some() { 0 } else { self.prize_budget() }
+    }
+
+    fn prize_budget(&self) -> u128 {
+        let prizes = if !self.placements.is_empty() {
+            self.placements.iter().sum()
+        } else if self.prize_split.is_some() {
+            self.max_prize_amount
+        } else {
+            self.max_prize_amount.saturating_mul(self.max_winners.unwrap_or(1) as u128)
+        };
+        prizes.saturating_add(self.first_solver_bonus.unwrap_or(0))
+    }
+
+    fn payout_params(&self, placement: Option<u64>) -> PayoutParams<'_> {
+        PayoutParams {
+            base_prize: self.base_prize(placement),
+            stage_payout_bps: &self.stage_payout_bps,
+            stale_policy: self.stale_policy.as_ref(),
+            retry_decay: placement.is_none(),
+            payout_schedule: &self.payout_schedule
+        }
+    }
+
+    fn unsolved_sec(&self) -> u64 {
+        match self.published_at {
+            Some(published_at) => {
+                let until = self.first_solved_at.unwrap_or_else(env::block_timestamp);
+                until.saturating_sub(published_at) / NANOS_PER_SEC
+            }
+            None => 0
+        }
+    }
+
+    fn time_decay_bps(&self) -> u32 {
+        match (self.published_at, self.expires_at) {
+            (Some(published_at), Some(expires_at)) => {
+                let elapsed = env::block_timestamp().saturating_sub(published_at) / NANOS_PER_SEC;
+                decay_bps(self.time_decay.as_ref(), elapsed, expires_at.saturating_sub(published_at) / NANOS_PER_SEC)
+            }
+            _ => BASIS_POINTS as u32
+        }
+    }
+
+    fn effective_prize(&self) -> u128 {
+        let boosted = boosted_prize(self.base_prize(self.next_placement()), self.stale_policy.as_ref(), self.unsolved_sec(), Some(self.escrow));
+        apply_bps(boosted, self.time_decay_bps() as u128)
+    }
+
+    fn global_attempts_remaining(&self) -> Option<u64> {
+        self.global_attempt_cap.map(|cap| cap.saturating_sub(self.total_attempts))
+    }
+
+    fn to_json_draft(&self, quiz_id: QuizId) -> JsonQuizDraft {
+        JsonQuizDraft {
+            quiz_id,
+            question: self.question.clone(),
+            prize_amount: U128(self.max_prize_amount),
+            status: self.status
+        }
+    }
+
+    fn to_json_quiz(&self, quiz_id: QuizId) -> JsonQuiz {
+        let global_attempts_remaining = self.global_attempts_remaining();
+        JsonQuiz {
+            quiz_id,
+            question: self.visible_question(),
+            prize_amount: U128(self.effective_prize()),
+            status: self.status,
+            epoch_height: self.epoch_height,
+            stale: self.is_stale(),
+            global_attempts_remaining,
+            guess_budget_exhausted: global_attempts_remaining == Some(0),
+            remaining_placements: self.remaining_placements(),
+            expires_at: self.expires_at,
+            expired: self.is_expired(),
+            category: self.category.clone(),
+            tags: self.tags.clone(),
+            difficulty: self.difficulty,
+            metadata: self.metadata.clone(),
+            prerequisites: self.prerequisites.clone(),
+            normalize_answers: self.normalize_answers,
+            commit_reveal: self.commit_reveal,
+            hash_algo: self.hash_algo,
+            max_retries: self.max_retries,
+            retry_price: self.retry_price.map(U128),
+            has_hint: self.hint.is_some(),
+            hint_penalty_bps: self.hint_penalty_bps,
+            attempt_window_sec: self.attempt_window_sec,
+            cooldown_sec: self.cooldown_sec,
+            stake_required: self.stake_required.map(U128),
+            first_solver_bonus: self.first_solver_bonus.map(U128),
+            prize_token: self.prize_token.clone(),
+            nft_prize: self.nft_prize.as_ref().map(|nft| nft.token.clone()),
+            prize_split: self.prize_split,
+            pool_entry_fees: self.pool_entry_fees,
+            entry_refund_rake_bps: self.entry_refund_rake_bps,
+            answer_parts: self.answer_count(),
+            numeric_tolerance: match &self.kind {
+                QuizKind::Numeric { tolerance, .. } => Some(*tolerance),
+                _ => None
+            },
+            steps: match &self.kind {
+                QuizKind::Sequence { steps } => Some(steps.len()),
+                _ => None
+            },
+            options: match &self.kind {
+                QuizKind::MultipleChoice { options, .. } => Some(options.clone()),
+                _ => None
+            }
+        }
+    }
+
+    fn answer_hash(&self, answer: &str) -> String {
+        util::salted_hash(self.hash_algo, &self.answer_salt, answer)
+    }
+
+    fn is_correct(&self, answer_hash: &str) -> bool {
+        self.correct_hashes.iter().any(|correct_hash| correct_hash == answer_hash)
+    }
+
+    // Returns how many parts are right out of how many the quiz has; single-answer quizzes have one part.
+    fn answer_count(&self) -> usize {
+        match &self.kind {
+            QuizKind::MultiPart { part_hashes } => part_hashes.len(),
+            _ => 1
+        }
+    }
+
+    fn grade(&self, answers: &[String], step: usize) -> (usize, usize) {
+        match &self.kind {
+            QuizKind::MultiPart { part_hashes } => {
+                assert!(answers.len() == part_hashes.len(), "This quiz takes {} answers", part_hashes.len());
+                let correct = answers.iter().zip(part_hashes).filter(|(answer, part_hash)| self.answer_hash(answer) == **part_hash).count();
+                (correct, part_hashes.len())
+            }
+            QuizKind::Numeric { target, tolerance } => {
+                assert!(answers.len() == 1, "This quiz takes a single answer");
+                let within = answers[0].trim().parse::<i64>().is_ok_and(|answer| answer.abs_diff(*target) <= *tolerance);
+                (within as usize, 1)
+            }
+            QuizKind::Sequence { steps } => {
+                assert!(answers.len() == 1, "This quiz takes a single answer");
+                let answer_hash = self.answer_hash(&answers[0]);
+                (steps[step].correct_hashes.contains(&answer_hash) as usize, 1)
+            }
+            _ => {
+                assert!(answers.len() == 1, "This quiz takes a single answer");
+                (self.is_correct(&self.answer_hash(&answers[0])) as usize, 1)
+            }
+        }
+    }
+
+    // The step that follows a correct answer to `step`, or None once the last one is answered.
+    fn next_step(&self, step: usize) -> Option<usize> {
+        match &self.kind {
+            QuizKind::Sequence { steps } => Some(step + 1).filter(|&next| next < steps.len()),
+            _ => None
+        }
+    }
+
+    fn step_question(&self, step: usize) -> String {
+        match &self.kind {
+            QuizKind::Sequence { steps } => steps[step].question.clone(),
+            _ => self.visible_question()
+        }
+    }
+
+    fn prepare_answer(&self, answer: String) -> String {
+        if self.normalize_answers { util::normalize_answer(&answer) } else { answer }
+    }
+
+    fn winners_exhausted(&self) -> bool {
+        self.max_winners.is_some_and(|max_winners| self.winner_count >= max_winners)
+    }
+
+    // Everything counted per round starts over, as if the quiz had just been published.
+    fn start_round(&mut self) {
+        if self.epoch_locked {
+            self.epoch_height = Some(env::epoch_height());
+        }
+        self.round += 1;
+        self.published_at = Some(env::block_timestamp());
+        self.solver_count = 0;
+        self.winner_count = 0;
+        self.total_attempts = 0;
+        self.first_solved_at = None;
+        self.first_solver = None;
+    }
+
+    // Has gone live at some point, so closed and archived quizzes count but drafts and scheduled ones don't.
+    fn has_gone_live(&self) -> bool {
+        self.status != QuizStatus::Unpublished && self.published_at.is_some_and(|published_at| env::block_timestamp() >= published_at)
+    }
+
+    fn is_live(&self) -> bool {
+        self.status == QuizStatus::Published && self.published_at.is_some_and(|published_at| env::block_timestamp() >= published_at)
+    }
+
+    fn accepting_answers(&self) -> bool {
+        self.is_live() && !self.is_stale() && !self.is_expired() && !self.placements_exhausted() && self.global_attempts_remaining() != Some(0)
+    }
+
+    fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|expires_at| env::block_timestamp() >= expires_at)
+    }
+
+    // Once the contract has closed nothing can be submitted, so every quiz is over.
+    fn escrow_refundable(&self, phase: ContractPhase) -> bool {
+        phase == ContractPhase::Closed || self.status != QuizStatus::Published || self.is_expired()
+    }
+
+    fn time_remaining_sec(&self) -> Option<u64> {
+        self.expires_at.map(|expires_at| expires_at.saturating_sub(env::block_timestamp()) / NANOS_PER_SEC)
+    }
+
+    fn is_stale(&self) -> bool {
+        self.epoch_locked && self.epoch_height.is_some_and(|epoch_height| env::epoch_height() > epoch_height)
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+pub struct RetryRecord {
+    round: u32,
+    retries_left: usize,
+    insurance: Option<u128>,
+    hint_used: bool,
+    last_failed_at: Option<u64>,
+    step: usize
+}
+
+#[derive(Clone, Copy)]
+struct SolvedAttempt {
+    attempt_number: u8,
+    placement: Option<u64>,
+    credit_bps: u32
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+pub struct AnswerCommitment {
+    round: u32,
+    commitment: String,
+    block_height: u64
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+pub struct AttemptStart {
+    round: u32,
+    started_at: u64
+}
+
+fn account_prefix(tag: u8, account_id: &AccountId) -> Vec<u8> {
+    let mut prefix = Vec::with_capacity(33);
+    prefix.push(tag);
+    prefix.extend(env::sha256(account_id.as_bytes()));
+    prefix
+}
+
+fn account_quiz_prefix(tag: u8, account_id: &AccountId, quiz_id: QuizId) -> Vec<u8> {
+    let mut prefix = account_prefix(tag, account_id);
+    prefix.extend(quiz_id.to_le_bytes());
+    prefix
+}
+
+fn category_prefix(category: &str) -> Vec<u8> {
+    let mut prefix = Vec::with_capacity(33);
+    prefix.push(b'c');
+    prefix.extend(env::sha256(category.as_bytes()));
+    prefix
+}
+
+fn category_key(category: &str) -> String {
+    category.trim().to_lowercase()
+}
+
+fn quiz_prefix(tag: u8, quiz_id: QuizId) -> Vec<u8> {
+    let mut prefix = Vec::with_capacity(9);
+    prefix.push(tag);
+    prefix.extend(quiz_id.to_le_bytes());
+    prefix
+}
+
+fn question_key(question: &str) -> String {
+    format!("{:x}", Sha256::digest(question.trim().to_lowercase().as_bytes()))
+}
+
+fn build_quiz(input: QuizInput, default_retries: usize) -> (Quiz, bool) {
+    let max_prize_amount = match (input.max_prize_amount, input.max_prize_near) {
+        (Some(max_prize_amount), None) => max_prize_amount.0,
+        (None, Some(max_prize_near)) => util::parse_near(&max_prize_near).unwrap_or_else(|err| panic!("Invalid max_prize_near: {}", err)),
+        _ => panic!("Provide exactly one of max_prize_amount or max_prize_near")
+    };
+
+    let options = input.options.unwrap_or_default();
+    // A scheduled quiz is published up front and simply stays hidden until publish_at.
+    let publish = input.publish || options.publish_at.is_some();
+    let status = if publish { QuizStatus::Published } else { QuizStatus::Unpublished };
+    let epoch_height = if publish && options.epoch_locked { Some(env::epoch_height()) } else { None };
+    let published_at = if publish { Some(options.publish_at.unwrap_or_else(env::block_timestamp)) } else { None };
+
+    let question = if options.question_parts.is_empty() {
+        input.question
+    } else {
+        assert!(input.question.is_empty(), "Pass either a question or question_parts, not both");
+        options.question_parts.join(" ")
+    };
+    assert!(
+        options.stage_payout_bps.is_empty() || options.stage_payout_bps.len() == options.question_parts.len(),
+        "stage_payout_bps must have one entry per question part"
+    );
+    let max_retries = options.max_retries.unwrap_or(default_retries);
+    assert!((1..=MAX_RETRIES).contains(&max_retries), "max_retries must be between 1 and {}", MAX_RETRIES);
+    assert!(
+        options.fee_schedule.is_empty() || options.fee_schedule.len() == max_retries,
+        "fee_schedule must have one entry per attempt ({})", max_retries
+    );
+    assert!(
+        options.payout_schedule.is_empty() || options.payout_schedule.len() == max_retries,
+        "payout_schedule must have one entry per attempt ({})", max_retries
+    );
+    assert!(
+        options.payout_schedule.iter().all(|&bps| bps <= BASIS_POINTS as u32),
+        "payout_schedule entries cannot exceed {} bps", BASIS_POINTS
+    );
+    assert!(options.payout_schedule.is_empty() || options.placements.is_empty(), "Placements are paid as listed, without a payout_schedule");
+    assert!(
+        options.expires_at.is_none_or(|expires_at| expires_at > env::block_timestamp()),
+        "expires_at must be in the future"
+    );
+    assert!(
+        options.publish_at.is_none_or(|publish_at| publish_at > env::block_timestamp()),
+        "publish_at must be in the future"
+    );
+    assert!(
+        options.expires_at.is_none() || options.expires_at > published_at,
+        "expires_at must be after the quiz is published"
+    );
+    assert!(
+        options.max_winners.is_none() || options.placements.is_empty(),
+        "Pass either max_winners or placements, not both"
+    );
+    assert!(options.prize_split.is_none() || options.max_winners.is_some(), "A split prize needs max_winners");
+    assert!(
+        !options.pool_entry_fees || (options.prize_split.is_some() && !options.fee_schedule.is_empty() && options.prize_token.is_none()),
+        "Pooling entry fees needs a NEAR split prize and a fee_schedule"
+    );
+    if let Some(rake_bps) = options.entry_refund_rake_bps {
+        assert!(!options.fee_schedule.is_empty() && !options.pool_entry_fees, "Refunding entry fees needs a fee_schedule that is not pooled");
+        assert!(rake_bps as u128 <= BASIS_POINTS, "entry_refund_rake_bps cannot exceed {}", BASIS_POINTS);
+    }
+    match &options.kind {
+        QuizKind::MultiPart { part_hashes } => {
+            assert!(part_hashes.len() >= 2, "A multi-part quiz needs at least two parts");
+            assert!(input.correct_hashes.is_empty(), "A multi-part quiz takes part_hashes instead of correct_hashes");
+        }
+        QuizKind::Sequence { steps } => {
+            assert!(steps.len() >= 2, "A sequence quiz needs at least two steps");
+            assert!(steps.iter().all(|step| !step.correct_hashes.is_empty()), "Every step needs at least one correct hash");
+            assert!(input.correct_hashes.is_empty(), "A sequence quiz takes correct hashes per step");
+        }
+        QuizKind::ManualReview => assert!(input.correct_hashes.is_empty(), "A manually reviewed quiz has no correct_hashes"),
+        QuizKind::Numeric { .. } => assert!(input.correct_hashes.is_empty(), "A numeric quiz is graded against its target, not correct_hashes"),
+        _ => assert!(!input.correct_hashes.is_empty(), "Provide at least one correct hash")
+    }
+    assert!(options.hint_penalty_bps as u128 <= BASIS_POINTS, "hint_penalty_bps cannot exceed {}", BASIS_POINTS);
+    if let QuizKind::MultipleChoice { options, salt } = &options.kind {
+        assert!(options.len() >= 2, "A multiple choice quiz needs at least two options");
+        assert!(!salt.is_empty(), "A multiple choice quiz needs a salt");
+    }
+    assert!(
+        options.category.as_ref().is_none_or(|category| !category_key(category).is_empty()),
+        "category cannot be empty"
+    );
+    assert!(
+        options.stale_policy.as_ref().is_none_or(|policy| policy.after_sec > 0),
+        "stale_policy.after_sec must be positive"
+    );
+    if let Some(decay) = &options.time_decay {
+        assert!(options.expires_at.is_some(), "time_decay needs expires_at");
+        assert!(decay.floor_bps <= BASIS_POINTS as u32, "time_decay.floor_bps cannot exceed {}", BASIS_POINTS);
+        assert!(decay.step_sec != Some(0), "time_decay.step_sec must be positive");
+    }
+    if let Some(vesting) = &options.vesting {
+        assert!(vesting.tranches > 0 && vesting.interval_sec > 0, "vesting needs at least one tranche and a positive interval");
+        assert!(options.prize_token.is_none(), "Only NEAR prizes can vest");
+    }
+    assert!(options.attempt_window_sec != Some(0), "attempt_window_sec must be positive");
+
+    let quiz = Quiz {
+        question, correct_hashes: input.correct_hashes, max_prize_amount, status,
+        epoch_locked: options.epoch_locked, epoch_height, round: 0,
+        question_parts: options.question_parts, reveal_interval_sec: options.reveal_interval_sec,
+        stage_payout_bps: options.stage_payout_bps, payout_schedule: options.payout_schedule, published_at, solver_count: 0,
+        global_attempt_cap: options.global_attempt_cap, total_attempts: 0, ever_attempted: false,
+        fee_schedule: options.fee_schedule.into_iter().map(|fee| fee.0).collect(),
+        practice_disabled: options.practice_disabled, practice_attempts: 0,
+        stale_policy: options.stale_policy, time_decay: options.time_decay, vesting: options.vesting, first_solved_at: None,
+        feedback_hashes: options.feedback_hashes,
+        insurance_price: options.insurance_price.map(|price| price.0),
+        placements: options.placements.into_iter().map(|prize| prize.0).collect(),
+        expires_at: options.expires_at,
+        category: options.category.map(|category| category_key(&category)),
+        tags: options.tags,
+        difficulty: options.difficulty,
+        previous_hashes: vec![],
+        max_winners: options.max_winners,
+        winner_count: 0,
+        metadata: options.metadata,
+        prerequisites: options.prerequisites,
+        normalize_answers: options.normalize_answers,
+        commit_reveal: options.commit_reveal,
+        answer_salt: options.answer_salt,
+        hash_algo: options.hash_algo,
+        max_retries,
+        retry_price: options.retry_price.map(|price| price.0),
+        hint: options.hint,
+        hint_penalty_bps: options.hint_penalty_bps,
+        attempt_window_sec: options.attempt_window_sec,
+        stake_required: options.stake_required.map(|stake| stake.0).filter(|&stake| stake > 0),
+        cooldown_sec: options.cooldown_sec,
+        first_solver_bonus: options.first_solver_bonus.map(|bonus| bonus.0),
+        first_solver: None,
+        prize_token: options.prize_token,
+        nft_prize: options.nft_prize.map(|token| NftPrize { token, deposited_by: None, winner: None, delivered: false }),
+        prize_split: options.prize_split,
+        pool_entry_fees: options.pool_entry_fees,
+        entry_refund_rake_bps: options.entry_refund_rake_bps,
+        escrow: 0,
+        funded: 0,
+        paid_out: 0,
+        deposits_held: 0,
+        kind: options.kind
+    };
+
+    (quiz, options.allow_duplicate)
+}
+
+#[near_bindgen]
+#[derive(BorshSerialize, BorshDeserialize, PanicOnDefault)]
+pub struct QuizContract {
+    owner_id: AccountId,
+    quizzes: LookupMap<QuizId, Quiz>,
+    published_quiz_ids: UnorderedSet<QuizId>,
+    solved_quizzes: LookupMap<AccountId, UnorderedSet<QuizId>>,
+    retries_left: LookupMap<AccountId, LookupMap<QuizId, RetryRecord>>,
+    draft_reviewers: LookupMap<QuizId, UnorderedSet<AccountId>>,
+    deleted_quiz_ids: UnorderedSet<QuizId>,
+    approved_submitters: LookupMap<AccountId, UnorderedSet<AccountId>>,
+    question_index: LookupMap<String, QuizId>,
+    payout_records: Vector<PayoutRecord>,
+    account_payouts: LookupMap<AccountId, Vector<u64>>,
+    season_pass_config: Option<SeasonPassConfig>,
+    season: u32,
+    season_passes: LookupMap<AccountId, SeasonPass>,
+    withdrawable_balance: u128,
+    jackpot: u128,
+    treasury: Option<TreasuryConfig>,
+    accrued_fees: u128,
+    accrued_token_fees: UnorderedMap<AccountId, u128>,
+    payout_preferences: LookupMap<AccountId, u128>,
+    claimable_prizes: LookupMap<AccountId, u128>,
+    signing_keys: LookupMap<AccountId, PublicKey>,
+    signing_nonces: LookupMap<AccountId, u64>,
+    activity_log: Vector<ActivityEntry>,
+    activity_head: u64,
+    activity_retention: u64,
+    wagers: LookupMap<AccountId, LookupMap<QuizId, Wager>>,
+    pending_wagers: LookupMap<AccountId, QuizId>,
+    quiz_participants: LookupMap<QuizId, UnorderedSet<AccountId>>,
+    category_quiz_ids: LookupMap<String, UnorderedSet<QuizId>>,
+    category_names: UnorderedSet<String>,
+    difficulty_multipliers: DifficultyMultipliers,
+    streak_config: StreakConfig,
+    referrals: LookupMap<AccountId, Referral>,
+    referrers: LookupMap<AccountId, ReferrerStats>,
+    referral_config: ReferralConfig,
+    account_tallies: LookupMap<AccountId, AccountTally>,
+    leaderboard: Vec<LeaderboardEntry>,
+    total_attempts: u64,
+    total_solves: u64,
+    unique_solvers: u64,
+    near_distributed: u128,
+    default_retries: usize,
+    series: Vector<String>,
+    series_quiz_ids: LookupMap<SeriesId, UnorderedSet<QuizId>>,
+    quiz_history: LookupMap<QuizId, Vector<StatusChange>>,
+    answer_commitments: LookupMap<AccountId, LookupMap<QuizId, AnswerCommitment>>,
+    attempt_starts: LookupMap<AccountId, LookupMap<QuizId, AttemptStart>>,
+    attempt_history: LookupMap<AccountId, LookupMap<QuizId, Vector<AttemptRecord>>>,
+    submissions: LookupMap<AccountId, LookupMap<QuizId, Submission>>,
+    review_queues: LookupMap<QuizId, UnorderedSet<AccountId>>,
+    graders: UnorderedSet<AccountId>,
+    quiz_funders: LookupMap<QuizId, UnorderedMap<AccountId, u128>>,
+    quiz_sponsors: LookupMap<QuizId, Vector<Sponsorship>>,
+    claimable_tokens: LookupMap<AccountId, LookupMap<AccountId, u128>>,
+    split_winners: LookupMap<QuizId, Vector<SplitShare>>,
+    streaks: LookupMap<AccountId, Streak>,
+    vesting: LookupMap<AccountId, Vector<VestingGrant>>,
+    stakes: LookupMap<QuizId, LookupMap<AccountId, u128>>,
+    entry_fees: LookupMap<QuizId, LookupMap<AccountId, u128>>,
+    storage_deposits: LookupMap<AccountId, u128>,
+    storage_required: bool,
+    payout_cap: Option<PayoutCap>,
+    winnings: LookupMap<AccountId, AccountWinnings>,
+    phase: ContractPhase,
+    closes_at: Option<u64>,
+    paused: bool,
+    pull_payouts: bool,
+    state_version: u32,
+    current_quiz_id: QuizId
+}
+
+#[near_bindgen]
+impl QuizContract {
+    #[init]
+    pub fn new(owner_id: AccountId) -> Self {
+        Self {
+            owner_id,
+            quizzes: LookupMap::new(StorageKey::Quizzes),
+            published_quiz_ids: UnorderedSet::new(StorageKey::PublishedQuizzes),
+            solved_quizzes: LookupMap::new(StorageKey::SolvedQuizzes),
+            retries_left: LookupMap::new(StorageKey::RetryRecords),
+            draft_reviewers: LookupMap::new(StorageKey::DraftReviewers),
+            deleted_quiz_ids: UnorderedSet::new(StorageKey::DeletedQuizzes),
+            approved_submitters: LookupMap::new(StorageKey::ApprovedSubmitters),
+            question_index: LookupMap::new(StorageKey::QuestionIndex),
+            payout_records: Vector::new(StorageKey::PayoutRecords),
+            account_payouts: LookupMap::new(StorageKey::AccountPayouts),
+            season_pass_config: None,
+            season: 0,
+            season_passes: LookupMap::new(StorageKey::SeasonPasses),
+            withdrawable_balance: 0,
+            jackpot: 0,
+            treasury: None,
+            accrued_fees: 0,
+            accrued_token_fees: UnorderedMap::new(StorageKey::AccruedTokenFees),
+            payout_preferences: LookupMap::new(StorageKey::PayoutPreferences),
+            claimable_prizes: LookupMap::new(StorageKey::ClaimablePrizes),
+            signing_keys: LookupMap::new(StorageKey::SigningKeys),
+            signing_nonces: LookupMap::new(StorageKey::SigningNonces),
+            activity_log: Vector::new(StorageKey::ActivityLog),
+            activity_head: 0,
+            activity_retention: DEFAULT_ACTIVITY_RETENTION,
+            wagers: LookupMap::new(StorageKey::Wagers),
+            pending_wagers: LookupMap::new(StorageKey::PendingWagers),
+            quiz_participants: LookupMap::new(StorageKey::QuizParticipants),
+            category_quiz_ids: LookupMap::new(StorageKey::CategoryQuizzes),
+            category_names: UnorderedSet::new(StorageKey::CategoryNames),
+            difficulty_multipliers: DifficultyMultipliers::default(),
+            streak_config: StreakConfig::default(),
+            referrals: LookupMap::new(StorageKey::Referrals),
+            referrers: LookupMap::new(StorageKey::Referrers),
+            referral_config: ReferralConfig::default(),
+            account_tallies: LookupMap::new(StorageKey::AccountTallies),
+            leaderboard: vec![],
+            total_attempts: 0,
+            total_solves: 0,
+            unique_solvers: 0,
+            near_distributed: 0,
+            default_retries: DEFAULT_RETRIES,
+            series: Vector::new(StorageKey::Series),
+            series_quiz_ids: LookupMap::new(StorageKey::SeriesQuizzes),
+            quiz_history: LookupMap::new(StorageKey::QuizHistory),
+            answer_commitments: LookupMap::new(StorageKey::AnswerCommitments),
+            attempt_starts: LookupMap::new(StorageKey::AttemptStarts),
+            attempt_history: LookupMap::new(StorageKey::AttemptHistory),
+            submissions: LookupMap::new(StorageKey::Submissions),
+            review_queues: LookupMap::new(StorageKey::ReviewQueues),
+            graders: UnorderedSet::new(StorageKey::Graders),
+            quiz_funders: LookupMap::new(StorageKey::QuizFunders),
+            quiz_sponsors: LookupMap::new(StorageKey::QuizSponsors),
+            claimable_tokens: LookupMap::new(StorageKey::ClaimableTokens),
+            split_winners: LookupMap::new(StorageKey::SplitWinners),
+            streaks: LookupMap::new(StorageKey::Streaks),
+            vesting: LookupMap::new(StorageKey::Vesting),
+            stakes: LookupMap::new(StorageKey::Stakes),
+            entry_fees: LookupMap::new(StorageKey::EntryFees),
+            storage_deposits: LookupMap::new(StorageKey::StorageDeposits),
+            storage_required: false,
+            payout_cap: None,
+            winnings: LookupMap::new(StorageKey::Winnings),
+            phase: ContractPhase::Active,
+            paused: false,
+            pull_payouts: false,
+            closes_at: None,
+            state_version: migrate::STATE_VERSION,
+            current_quiz_id: 0
+        }
+    }
+
+    #[payable]
+    pub fn submit_answer(&mut self, quiz_id: QuizId, answer: String, payout_to: Option<AccountId>) -> SubmitResult {
+        self.enter_phase(ACCEPTING_ANSWERS);
+        if payout_to == Some(env::current_account_id()) {
+            panic_json(QuizError::PayoutToContract);
+        }
+
+        self.internal_submit_answer(env::predecessor_account_id(), quiz_id, vec![answer], payout_to, false)
+    }
+
+    // Items that would be rejected come back as errors instead of failing the batch. Batches carry no
+    // deposit, so quizzes with an attempt fee have to be answered one by one.
+    pub fn submit_answer_batch(&mut self, answers: Vec<(QuizId, String)>) -> Vec<BatchSubmitResult> {
+        self.enter_phase(ACCEPTING_ANSWERS);
+        self.check_not_paused();
+        assert!(answers.len() <= MAX_BATCH_SUBMISSIONS, "A batch can hold at most {} answers", MAX_BATCH_SUBMISSIONS);
+        let account_id = env::predecessor_account_id();
+
+        let mut results = Vec::with_capacity(answers.len());
+        for (quiz_id, answer) in answers {
+            let error = match self.quizzes.get(&quiz_id) {
+                Some(quiz) => self.submission_error(&account_id, quiz_id, &quiz, false).or_else(|| self.batch_error(&account_id, quiz_id, &quiz)),
+                None if self.deleted_quiz_ids.contains(&quiz_id) => Some(QuizError::QuizDeleted { quiz_id }),
+                None => Some(QuizError::QuizNotFound { quiz_id })
+            };
+
+            results.push(match error {
+                Some(error) => BatchSubmitResult { quiz_id, result: None, error: Some(error) },
+                None => {
+                    let result = self.internal_submit_answer(account_id.clone(), quiz_id, vec![answer], None, false);
+                    BatchSubmitResult { quiz_id, result: Some(result), error: None }
+                }
+            });
+        }
+
+        results
+    }
+
+    #[payable]
+    pub fn submit_answers(&mut self, quiz_id: QuizId, answers: Vec<String>, payout_to: Option<AccountId>) -> SubmitResult {
+        self.enter_phase(ACCEPTING_ANSWERS);
+        if payout_to == Some(env::current_account_id()) {
+            panic_json(QuizError::PayoutToContract);
+        }
+
+        self.internal_submit_answer(env::predecessor_account_id(), quiz_id, answers, payout_to, false)
+    }
+
+    #[payable]
+    pub fn submit_numeric_answer(&mut self, quiz_id: QuizId, answer: i64, payout_to: Option<AccountId>) -> SubmitResult {
+        self.enter_phase(ACCEPTING_ANSWERS);
+        if payout_to == Some(env::current_account_id()) {
+            panic_json(QuizError::PayoutToContract);
+        }
+        assert!(matches!(self.expect_quiz(quiz_id).kind, QuizKind::Numeric { .. }), "This is not a numeric quiz");
+
+        self.internal_submit_answer(env::predecessor_account_id(), quiz_id, vec![answer.to_string()], payout_to, false)
+    }
+
+    #[payable]
+    pub fn submit_choice(&mut self, quiz_id: QuizId, option_index: u32, payout_to: Option<AccountId>) -> SubmitResult {
+        self.enter_phase(ACCEPTING_ANSWERS);
+        if payout_to == Some(env::current_account_id()) {
+            panic_json(QuizError::PayoutToContract);
+        }
+
+        let QuizKind::MultipleChoice { options, salt } = self.expect_quiz(quiz_id).kind else {
+            panic!("This is not a multiple choice quiz");
+        };
+        assert!((option_index as usize) < options.len(), "There are only {} options", options.len());
+
+        self.internal_submit_answer(env::predecessor_account_id(), quiz_id, vec![util::choice_answer(&salt, option_index)], payout_to, false)
+    }
+
+    #[payable]
+    pub fn submit_answer_for(&mut self, quiz_id: QuizId, answer: String, beneficiary: AccountId) -> SubmitResult {
+        self.enter_phase(ACCEPTING_ANSWERS);
+        let submitter = env::predecessor_account_id();
+        let approved = self.approved_submitters.get(&beneficiary).is_some_and(|submitters| submitters.contains(&submitter));
+        if !approved {
+            panic_json(QuizError::SubmitterNotApproved { submitter, beneficiary });
+        }
+
+        self.internal_submit_answer(beneficiary, quiz_id, vec![answer], None, false)
+    }
+
+    #[payable]
+    pub fn submit_answer_signed(&mut self, quiz_id: QuizId, answer: String, account_id: AccountId, nonce: u64, signature: Base64VecU8) -> SubmitResult {
+        self.enter_phase(ACCEPTING_ANSWERS);
+        let public_key = self.signing_keys.get(&account_id)
+            .map(|public_key| public_key.as_bytes()[1..].to_vec())
+            .or_else(|| util::implicit_account_key(&account_id))
+            .unwrap_or_else(|| panic_json(QuizError::SigningKeyNotRegistered { account_id: account_id.clone() }));
+        if let Some(last_nonce) = self.signing_nonces.get(&account_id).filter(|&last_nonce| nonce <= last_nonce) {
+            panic_json(QuizError::NonceReused { nonce, last_nonce });
+        }
+
+        let message = util::signed_answer_message(&env::current_account_id(), quiz_id, &answer, nonce);
+        if !util::verify_ed25519(&public_key, &message, &signature.0) {
+            panic_json(QuizError::InvalidSignature);
+        }
+        self.signing_nonces.insert(&account_id, &nonce);
+
+        self.internal_submit_answer(account_id, quiz_id, vec![answer], None, false)
+    }
+
+    pub fn commit_answer(&mut self, quiz_id: QuizId, commitment: String) -> u64 {
+        self.enter_phase(ACCEPTING_ANSWERS);
+        self.check_not_paused();
+        let account_id = env::predecessor_account_id();
+        let quiz = self.expect_quiz(quiz_id);
+        if !quiz.is_live() {
+            panic_json(QuizError::QuizNotPublished { quiz_id });
+        }
+
+        let mut commitments = self.answer_commitments.get(&account_id).unwrap_or_else(|| {
+            LookupMap::new(account_prefix(b'm', &account_id))
+        });
+        commitments.insert(&quiz_id, &AnswerCommitment { round: quiz.round, commitment, block_height: env::block_height() });
+        self.answer_commitments.insert(&account_id, &commitments);
+
+        env::block_height() + MIN_REVEAL_DELAY_BLOCKS
+    }
+
+    #[payable]
+    pub fn reveal_answer(&mut self, quiz_id: QuizId, answer: String, salt: String, payout_to: Option<AccountId>) -> SubmitResult {
+        self.enter_phase(ACCEPTING_ANSWERS);
+        if payout_to == Some(env::current_account_id()) {
+            panic_json(QuizError::PayoutToContract);
+        }
+
+        let account_id = env::predecessor_account_id();
+        let round = self.expect_quiz(quiz_id).round;
+        let mut commitments = self.answer_commitments.get(&account_id)
+            .filter(|commitments| commitments.get(&quiz_id).is_some_and(|commitment| commitment.round == round))
+            .unwrap_or_else(|| panic_json(QuizError::NoCommitment { quiz_id }));
+        let commitment = commitments.get(&quiz_id).unwrap();
+
+        let reveal_after = commitment.block_height + MIN_REVEAL_DELAY_BLOCKS;
+        if env::block_height() < reveal_after {
+            panic_json(QuizError::RevealTooEarly { quiz_id, reveal_after });
+        }
+        if util::answer_commitment(&account_id, quiz_id, &answer, &salt) != commitment.commitment {
+            panic_json(QuizError::CommitmentMismatch { quiz_id });
+        }
+
+        commitments.remove(&quiz_id);
+        self.answer_commitments.insert(&account_id, &commitments);
+
+        self.internal_submit_answer(account_id, quiz_id, vec![answer], payout_to, true)
+    }
+
+    pub fn register_signing_key(&mut self, public_key: PublicKey) {
+        self.enter_phase(ANY_PHASE);
+        if public_key.curve_type() != CurveType::ED25519 {
+            panic_json(QuizError::UnsupportedKeyType);
+        }
+
+        self.signing_keys.insert(&env::predecessor_account_id(), &public_key);
+    }
+
+    pub fn submit_practice_answer(&mut self, quiz_id: QuizId, answer: String) -> PracticeResult {
+        self.enter_phase(ACCEPTING_ANSWERS);
+        let mut quiz = self.expect_quiz(quiz_id);
+        assert!(quiz.is_live(), "Cannot practice on unpublished quiz");
+        assert!(!quiz.practice_disabled && quiz.kind != QuizKind::ManualReview, "Practice is disabled for this quiz");
+
+        quiz.practice_attempts += 1;
+        self.quizzes.insert(&quiz_id, &quiz);
+
+        let (parts_correct, parts) = quiz.grade(&[quiz.prepare_answer(answer)], 0);
+        let correct = parts_correct == parts;
+        env::log_str(&format!("practice attempt on quiz {}: {}", quiz_id, if correct { "correct" } else { "incorrect" }));
+
+        PracticeResult { correct }
+    }
+
+    #[payable]
+    pub fn buy_retry_insurance(&mut self, quiz_id: QuizId) {
+        self.enter_phase(ACCEPTING_ANSWERS);
+        let account_id = env::predecessor_account_id();
+        let quiz = self.expect_quiz(quiz_id);
+        assert!(quiz.is_live(), "Cannot insure a retry on unpublished quiz");
+        let price = quiz.insurance_price.expect("This quiz does not offer retry insurance");
+
+        let (solved, retries_left) = self.participation(&account_id, quiz_id, &quiz);
+        assert!(!solved, "This quiz is already solved by you");
+        assert!(retries_left > 0, "You need at least one retry left to insure it");
+        let record = self.retry_record(&account_id, quiz_id, &quiz);
+        assert!(record.insurance.is_none(), "You already hold retry insurance for this quiz");
+
+        let deposit = env::attached_deposit();
+        if deposit < price {
+            panic_json(QuizError::InsufficientDeposit { purpose: DepositPurpose::RetryInsurance, required: U128(price), attached: U128(deposit) });
+        }
+
+        let mut retries_left_map = self.retries_left.get(&account_id).unwrap_or_else(|| {
+            LookupMap::new(account_prefix(b'u', &account_id))
+        });
+        retries_left_map.insert(&quiz_id, &RetryRecord { insurance: Some(price), ..record });
+        self.retries_left.insert(&account_id, &retries_left_map);
+        self.track_participant(quiz_id, &account_id);
+
+        if deposit > price {
+            Promise::new(account_id).transfer(deposit - price);
+        }
+    }
+
+    #[payable]
+    pub fn buy_retry(&mut self, quiz_id: QuizId) {
+        self.enter_phase(ACCEPTING_ANSWERS);
+        self.check_not_paused();
+        let account_id = env::predecessor_account_id();
+        let quiz = self.expect_quiz(quiz_id);
+        assert!(quiz.accepting_answers(), "This quiz is not accepting answers");
+        let price = quiz.retry_price.expect("This quiz does not sell extra retries");
+
+        let (solved, retries_left) = self.participation(&account_id, quiz_id, &quiz);
+        assert!(!solved, "This quiz is already solved by you");
+        assert!(retries_left == 0, "You still have free retries left");
+        let record = self.retry_record(&account_id, quiz_id, &quiz);
+
+        let deposit = env::attached_deposit();
+        if deposit < price {
+            panic_json(QuizError::InsufficientDeposit { purpose: DepositPurpose::ExtraRetry, required: U128(price), attached: U128(deposit) });
+        }
+        self.withdrawable_balance += price;
+
+        let mut retries_left_map = self.retries_left.get(&account_id).unwrap_or_else(|| {
+            LookupMap::new(account_prefix(b'u', &account_id))
+        });
+        retries_left_map.insert(&quiz_id, &RetryRecord { retries_left: 1, ..record });
+        self.retries_left.insert(&account_id, &retries_left_map);
+
+        if deposit > price {
+            Promise::new(account_id).transfer(deposit - price);
+        }
+    }
+
+    pub fn start_attempt(&mut self, quiz_id: QuizId) -> u64 {
+        self.enter_phase(ACCEPTING_ANSWERS);
+        self.check_not_paused();
+        let account_id = env::predecessor_account_id();
+        let quiz = self.expect_quiz(quiz_id);
+        assert!(quiz.accepting_answers(), "This quiz is not accepting answers");
+        let window_sec = quiz.attempt_window_sec.expect("This quiz has no timed attempts");
+
+        let (solved, retries_left) = self.participation(&account_id, quiz_id, &quiz);
+        assert!(!solved, "This quiz is already solved by you");
+        assert!(retries_left > 0, "You are out of tries");
+
+        let mut starts = self.attempt_starts.get(&account_id).unwrap_or_else(|| {
+            LookupMap::new(account_prefix(b't', &account_id))
+        });
+        let now = env::block_timestamp();
+        let running = starts.get(&quiz_id)
+            .is_some_and(|start| start.round == quiz.round && now < start.started_at + window_sec * NANOS_PER_SEC);
+        assert!(!running, "An attempt on this quiz is already running");
+
+        starts.insert(&quiz_id, &AttemptStart { round: quiz.round, started_at: now });
+        self.attempt_starts.insert(&account_id, &starts);
+
+        now + window_sec * NANOS_PER_SEC
+    }
+
+    pub fn request_hint(&mut self, quiz_id: QuizId) -> String {
+        self.enter_phase(ACCEPTING_ANSWERS);
+        self.check_not_paused();
+        let account_id = env::predecessor_account_id();
+        let quiz = self.expect_quiz(quiz_id);
+        assert!(quiz.is_live(), "Cannot ask for a hint on unpublished quiz");
+        let hint = quiz.hint.clone().expect("This quiz has no hint");
+
+        let (solved, _) = self.participation(&account_id, quiz_id, &quiz);
+        assert!(!solved, "This quiz is already solved by you");
+        let record = self.retry_record(&account_id, quiz_id, &quiz);
+        if !record.hint_used {
+            let mut retries_left_map = self.retries_left.get(&account_id).unwrap_or_else(|| {
+                LookupMap::new(account_prefix(b'u', &account_id))
+            });
+            retries_left_map.insert(&quiz_id, &RetryRecord { hint_used: true, ..record });
+            self.retries_left.insert(&account_id, &retries_left_map);
+            self.track_participant(quiz_id, &account_id);
+        }
+
+        hint
+    }
+
+    pub fn approve_submitter(&mut self, submitter: AccountId) {
+        self.enter_phase(ACCEPTING_ANSWERS);
+        let account_id = env::predecessor_account_id();
+        let mut submitters = self.approved_submitters.get(&account_id).unwrap_or_else(|| {
+            UnorderedSet::new(account_prefix(b'a', &account_id))
+        });
+
+        submitters.insert(&submitter);
+        self.approved_submitters.insert(&account_id, &submitters);
+    }
+
+    pub fn revoke_submitter(&mut self, submitter: AccountId) {
+        self.enter_phase(ANY_PHASE);
+        let account_id = env::predecessor_account_id();
+        let mut submitters = self.approved_submitters.get(&account_id).expect("You have not approved any submitters");
+        assert!(submitters.remove(&submitter), "This submitter is not approved");
+
+        if submitters.is_empty() {
+            self.approved_submitters.remove(&account_id);
+        } else {
+            self.approved_submitters.insert(&account_id, &submitters);
+        }
+    }
+
+    fn internal_submit_answer(&mut self, account_id: AccountId, quiz_id: QuizId, answers: Vec<String>, payout_to: Option<AccountId>, revealed: bool) -> SubmitResult {
+        self.check_not_paused();
+        let mut quiz = self.expect_quiz(quiz_id);
+        if let Some(err) = self.submission_error(&account_id, quiz_id, &quiz, revealed) {
+            panic_json(err);
+        }
+        if answers.len() != quiz.answer_count() {
+            panic_json(QuizError::WrongAnswerCount { quiz_id, expected: quiz.answer_count() });
+        }
+        let mut retries_left_map: LookupMap<QuizId, RetryRecord> = self.retries_left.get(&account_id).unwrap_or_else(|| {
+            LookupMap::new(account_prefix(b'u', &account_id))
+        });
+
+        let (_, retries_left) = self.participation(&account_id, quiz_id, &quiz);
+        let record = self.retry_record(&account_id, quiz_id, &quiz);
+        if let Some(mut solved_quizzes_set) = self.solved_quizzes.get(&account_id) {
+            if solved_quizzes_set.remove(&quiz_id) {
+                self.solved_quizzes.insert(&account_id, &solved_quizzes_set);
+            }
+        }
+
+        let late = quiz.attempt_window_sec.is_some_and(|window_sec| {
+            let started_at = self.take_attempt_start(&account_id, quiz_id, &quiz)
+                .unwrap_or_else(|| panic_json(QuizError::AttemptNotStarted { quiz_id }));
+            env::block_timestamp() > started_at + window_sec * NANOS_PER_SEC
+        });
+        quiz.total_attempts += 1;
+        quiz.ever_attempted = true;
+        let mut tally = self.account_tallies.get(&account_id).unwrap_or_default();
+        tally.attempts += 1;
+        self.account_tallies.insert(&account_id, &tally);
+        self.total_attempts += 1;
+
+        let fee = self.attempt_fee(&account_id, &quiz, retries_left);
+        let deposit = env::attached_deposit();
+        if deposit < fee {
+            panic_json(QuizError::InsufficientDeposit { purpose: DepositPurpose::Attempt, required: U128(fee), attached: U128(deposit) });
+        }
+        self.collect_attempt_fee(quiz_id, &mut quiz, &account_id, fee);
+        if deposit > fee {
+            Promise::new(env::predecessor_account_id()).transfer(deposit - fee);
+        }
+
+        let mut answers: Vec<String> = answers.into_iter().map(|answer| quiz.prepare_answer(answer)).collect();
+        if quiz.kind == QuizKind::ManualReview && !late {
+            self.quizzes.insert(&quiz_id, &quiz);
+            self.queue_submission(&account_id, quiz_id, &quiz, answers.remove(0), payout_to);
+
+            return SubmitResult {
+                correct: false,
+                message: "Your answer is waiting for review".to_owned(),
+                amount: None,
+                retries_left,
+                feedback: None,
+                prize_pool_exhausted: false
+            };
+        }
+        let (parts_correct, parts) = quiz.grade(&answers, record.step);
+
+        self.record_attempt(&account_id, quiz_id, quiz.round, !late && parts_correct > 0);
+
+        if let Some(next_step) = quiz.next_step(record.step).filter(|_| !late && parts_correct > 0) {
+            self.quizzes.insert(&quiz_id, &quiz);
+            retries_left_map.insert(&quiz_id, &RetryRecord { step: next_step, ..record });
+            self.retries_left.insert(&account_id, &retries_left_map);
+            self.track_participant(quiz_id, &account_id);
+
+            return SubmitResult {
+                correct: true,
+                message: format!("Correct. On to step {}: {}", next_step + 1, quiz.step_question(next_step)),
+                amount: None,
+                retries_left,
+                feedback: None,
+                prize_pool_exhausted: false
+            };
+        }
+
+        if !late && parts_correct > 0 {
+            self.reward_solver(&account_id, quiz_id, quiz, record, payout_to, (parts_correct, parts))
+        } else {
+            let retries_left = self.record_miss(&account_id, quiz_id, &mut quiz, record);
+
+            let message = if late {
+                format!("Your answer came in after the attempt window. You have {} retries left", retries_left)
+            } else if retries_left == 0 {
+                "The answer is not right, you are out of tries".to_string()
+            } else {
+                format!("The answer is not right. You have {} retries left", retries_left)
+            };
+
+            let answer = &answers[0];
+            let feedback = quiz.feedback_hashes.as_ref().filter(|_| !late).map(|hints| AnswerFeedback {
+                length_matches: answer.chars().count() == hints.length as usize,
+                first_char_matches: util::first_char_hash(answer) == hints.first_char_hash
+            });
+
+            SubmitResult { correct: false, message, amount: None, retries_left, feedback, prize_pool_exhausted: false }
+        }
+    }
+
+    #[payable]
+    pub fn create_quiz(&mut self, question: String, correct_hashes: Vec<String>, max_prize_amount: Option<U128>, max_prize_near: Option<String>, publish: bool, options: Option<QuizOptions>) -> QuizId {
+        self.enter_phase(ACTIVE_ONLY);
+        self.check_owner();
+        self.check_not_paused();
+
+        let (mut quiz, allow_duplicate) = build_quiz(QuizInput { question, correct_hashes, max_prize_amount, max_prize_near, publish, options }, self.default_retries);
+        quiz.escrow = self.take_escrow(quiz.near_budget(), quiz.prize_token.is_none());
+        self.insert_quiz(quiz, allow_duplicate)
+    }
+
+    #[payable]
+    pub fn clone_quiz(&mut self, quiz_id: QuizId, publish: bool) -> QuizId {
+        self.enter_phase(ACTIVE_ONLY);
+        self.check_owner();
+        self.check_not_paused();
+
+        let source = self.expect_quiz(quiz_id);
+        let quiz = Quiz {
+            status: if publish { QuizStatus::Published } else { QuizStatus::Unpublished },
+            epoch_height: if publish && source.epoch_locked { Some(env::epoch_height()) } else { None },
+            round: 0,
+            published_at: if publish { Some(env::block_timestamp()) } else { None },
+            solver_count: 0,
+            total_attempts: 0,
+            ever_attempted: false,
+            practice_attempts: 0,
+            first_solved_at: None,
+            first_solver: None,
+            expires_at: None,
+            previous_hashes: vec![],
+            winner_count: 0,
+            escrow: self.take_escrow(source.near_budget(), source.prize_token.is_none()),
+            funded: 0,
+            paid_out: 0,
+            deposits_held: 0,
+            nft_prize: source.nft_prize.as_ref().map(|nft| NftPrize { token: nft.token.clone(), deposited_by: None, winner: None, delivered: false }),
+            ..source
+        };
+
+        self.insert_quiz(quiz, true)
+    }
+
+    #[payable]
+    pub fn create_quizzes(&mut self, inputs: Vec<QuizInput>) -> Vec<QuizId> {
+        self.enter_phase(ACTIVE_ONLY);
+        self.check_owner();
+        self.check_not_paused();
+
+        let quizzes: Vec<(Quiz, bool)> = inputs.into_iter().map(|input| {
+            let (mut quiz, allow_duplicate) = build_quiz(input, self.default_retries);
+            quiz.escrow = quiz.near_budget();
+            (quiz, allow_duplicate)
+        }).collect();
+        self.take_escrow(quizzes.iter().map(|(quiz, _)| quiz.escrow).fold(0, u128::saturating_add), false);
+
+        let mut batch_questions = HashMap::new();
+        for (offset, (quiz, allow_duplicate)) in quizzes.iter().enumerate() {
+            let quiz_id = self.current_quiz_id + offset as QuizId;
+            assert!(!self.deleted_quiz_ids.contains(&quiz_id), "Quiz id {} belongs to a deleted quiz and cannot be reused", quiz_id);
+
+            let key = question_key(&quiz.question);
+            if !allow_duplicate {
+                if let Some(existing_quiz_id) = self.duplicate_of(&key).or_else(|| batch_questions.get(&key).copied()) {
+                    panic!("duplicate question (see quiz {})", existing_quiz_id);
+                }
+            }
+            batch_questions.entry(key).or_insert(quiz_id);
+
+            for prerequisite in &quiz.prerequisites {
+                let earlier_in_batch = (self.current_quiz_id..quiz_id).contains(prerequisite);
+                assert!(earlier_in_batch || self.quizzes.contains_key(prerequisite), "Prerequisite quiz {} does not exist", prerequisite);
+            }
+        }
+
+        quizzes.into_iter().map(|(quiz, allow_duplicate)| self.insert_quiz(quiz, allow_duplicate)).collect()
+    }
+
+    // Anyone may sponsor a quiz; every contribution is tracked per funder.
+    #[payable]
+    pub fn fund_quiz(&mut self, quiz_id: QuizId) -> U128 {
+        self.enter_phase(ACTIVE_ONLY);
+        self.check_not_paused();
+
+        let quiz = self.expect_quiz(quiz_id);
+        if let Some(token) = &quiz.prize_token {
+            panic!("Quiz {} pays prizes in {}, fund it with ft_transfer_call", quiz_id, token);
+        }
+
+        let amount = env::attached_deposit();
+        if amount == 0 {
+            panic_json(QuizError::InsufficientDeposit { purpose: DepositPurpose::Escrow, required: U128(1), attached: U128(0) });
+        }
+
+        U128(self.add_funding(quiz_id, quiz, &env::predecessor_account_id(), amount))
+    }
+
+    // Funds the quiz like fund_quiz and keeps a public credit line for the UI.
+    #[payable]
+    pub fn sponsor_quiz(&mut self, quiz_id: QuizId, message: Option<String>) -> U128 {
+        let message = message.map(|message| message.trim().to_owned()).filter(|message| !message.is_empty());
+        assert!(
+            message.as_ref().is_none_or(|message| message.chars().count() <= MAX_SPONSOR_MESSAGE_LEN),
+            "A sponsor message can be at most {} characters", MAX_SPONSOR_MESSAGE_LEN
+        );
+        let balance = self.fund_quiz(quiz_id);
+
+        let mut sponsors = self.quiz_sponsors.get(&quiz_id).unwrap_or_else(|| {
+            Vector::new(quiz_prefix(b'n', quiz_id))
+        });
+        sponsors.push(&Sponsorship {
+            account_id: env::predecessor_account_id(),
+            amount: U128(env::attached_deposit()),
+            message,
+            timestamp: env::block_timestamp()
+        });
+        self.quiz_sponsors.insert(&quiz_id, &sponsors);
+
+        balance
+    }
+
+    pub fn get_sponsors(&self, quiz_id: QuizId) -> Vec<Sponsorship> {
+        self.quiz_sponsors.get(&quiz_id).map_or_else(Vec::new, |sponsors| sponsors.to_vec())
+    }
+
+    // NEP-141 receiver for token prize pools; msg is the id of the quiz to fund. Panicking sends the tokens back.
+    pub fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> PromiseOrValue<U128> {
+        self.enter_phase(ACTIVE_ONLY);
+        self.check_not_paused();
+
+        let quiz_id = msg.parse::<QuizId>().unwrap_or_else(|_| panic!("msg must be the id of the quiz to fund"));
+        let quiz = self.expect_quiz(quiz_id);
+        let token = env::predecessor_account_id();
+        assert!(quiz.prize_token.as_ref() == Some(&token), "Quiz {} does not pay prizes in {}", quiz_id, token);
+
+        self.add_funding(quiz_id, quiz, &sender_id, amount.0);
+        PromiseOrValue::Value(U128(0))
+    }
+
+    #[private]
+    pub fn on_prize_transfer(&mut self, receiver_id: AccountId, amount: U128) {
+        if let PromiseResult::Successful(_) = env::promise_result(0) {
+            return;
+        }
+
+        let accrued = self.claimable_prizes.get(&receiver_id).unwrap_or(0) + amount.0;
+        self.claimable_prizes.insert(&receiver_id, &accrued);
+
+        emit_event("prize_transfer_failed", json!({
+            "receiver_id": receiver_id,
+            "amount": amount
+        }));
+    }
+
+    #[private]
+    pub fn on_ft_transfer(&mut self, token_id: AccountId, receiver_id: AccountId, amount: U128) {
+        if let PromiseResult::Successful(_) = env::promise_result(0) {
+            return;
+        }
+
+        self.credit_tokens(&receiver_id, &token_id, amount.0);
+
+        emit_event("ft_transfer_failed", json!({
+            "token_id": token_id,
+            "receiver_id": receiver_id,
+            "amount": amount
+        }));
+    }
+
+    // NEP-171 receiver for NFT prizes; msg is the id of the quiz the token was announced for.
+    pub fn nft_on_transfer(&mut self, sender_id: AccountId, previous_owner_id: AccountId, token_id: String, msg: String) -> PromiseOrValue<bool> {
+        self.enter_phase(ACTIVE_ONLY);
+        self.check_not_paused();
+
+        let quiz_id = msg.parse::<QuizId>().unwrap_or_else(|_| panic!("msg must be the id of the quiz the NFT is for"));
+        let mut quiz = self.expect_quiz(quiz_id);
+        let token = NftToken { contract_id: env::predecessor_account_id(), token_id };
+        let nft = quiz.nft_prize.as_mut().filter(|nft| nft.token == token).unwrap_or_else(|| panic!("Quiz {} does not offer this NFT as a prize", quiz_id));
+        assert!(nft.deposited_by.is_none(), "The NFT prize for quiz {} is already deposited", quiz_id);
+        nft.deposited_by = Some(previous_owner_id.clone());
+        self.quizzes.insert(&quiz_id, &quiz);
+
+        emit_event("nft_prize_deposited", json!({
+            "quiz_id": quiz_id,
+            "sender_id": sender_id,
+            "previous_owner_id": previous_owner_id,
+            "token": token
+        }));
+
+        PromiseOrValue::Value(false)
+    }
+
+    #[private]
+    pub fn on_nft_transfer(&mut self, quiz_id: QuizId) {
+        let Some(mut quiz) = self.quizzes.get(&quiz_id) else {
+            return;
+        };
+        let nft = quiz.nft_prize.as_mut().unwrap();
+
+        if let PromiseResult::Successful(_) = env::promise_result(0) {
+            nft.delivered = true;
+            self.quizzes.insert(&quiz_id, &quiz);
+            return;
+        }
+
+        emit_event("nft_transfer_failed", json!({
+            "quiz_id": quiz_id,
+            "receiver_id": nft.winner,
+            "token": nft.token
+        }));
+    }
+
+    // Retries a delivery that failed; the winner stays recorded either way.
+    pub fn claim_nft(&mut self, quiz_id: QuizId) {
+        self.enter_phase(ANY_PHASE);
+        self.check_not_paused();
+
+        let quiz = self.expect_quiz(quiz_id);
+        let account_id = env::predecessor_account_id();
+        let nft = quiz.nft_prize.filter(|nft| nft.winner.as_ref() == Some(&account_id) && !nft.delivered).unwrap_or_else(|| panic_json(QuizError::NothingToClaim));
+        self.transfer_nft(quiz_id, &nft.token, &account_id);
+    }
+
+    // Hands an unwon NFT back to whoever deposited it, under the same conditions as refund_quiz.
+    pub fn reclaim_nft(&mut self, quiz_id: QuizId) {
+        self.enter_phase(ANY_PHASE);
+        self.check_owner();
+
+        let mut quiz = self.expect_quiz(quiz_id);
+        if !quiz.escrow_refundable(self.phase) {
+            panic_json(QuizError::EscrowLocked { quiz_id });
+        }
+        let nft = quiz.nft_prize.as_mut().filter(|nft| nft.winner.is_none()).unwrap_or_else(|| panic_json(QuizError::NothingToRefund));
+        let depositor = nft.deposited_by.clone().unwrap_or_else(|| panic_json(QuizError::NothingToRefund));
+        nft.winner = Some(depositor.clone());
+        let token = nft.token.clone();
+        self.quizzes.insert(&quiz_id, &quiz);
+
+        self.transfer_nft(quiz_id, &token, &depositor);
+    }
+
+    // Callable by anyone once the quiz is closed, expired or has all its winners.
+    // The pool is whatever escrow is left at that point, sponsor top-ups included; rounding dust stays for refund_quiz.
+    pub fn distribute_split(&mut self, quiz_id: QuizId) -> U128 {
+        self.enter_phase(ANY_PHASE);
+        self.check_not_paused();
+
+        let mut quiz = self.expect_quiz(quiz_id);
+        let split = quiz.prize_split.expect("This quiz does not split its prize");
+        if !quiz.escrow_refundable(self.phase) && !quiz.winners_exhausted() {
+            panic_json(QuizError::SplitPending { quiz_id });
+        }
+
+        let mut winners = self.split_winners.remove(&quiz_id).unwrap_or_else(|| panic_json(QuizError::NothingToClaim));
+        let shares = winners.to_vec();
+        winners.clear();
+
+        let weights: Vec<u128> = shares.iter().map(|share| match split {
+            PrizeSplit::Equal => 1,
+            PrizeSplit::ByAttempts => (quiz.max_retries + 2 - share.attempt_number as usize) as u128
+        }).collect();
+        let total_weight: u128 = weights.iter().sum();
+        let pool = quiz.escrow;
+        let treasury_fee_bps = self.treasury.as_ref().map_or(0, |treasury| treasury.fee_bps);
+
+        let mut paid = 0;
+        for (share, weight) in shares.iter().zip(weights) {
+            let params = PayoutParams { base_prize: pool * weight / total_weight, stage_payout_bps: &[], stale_policy: None, retry_decay: false, payout_schedule: &[] };
+            let context = PayoutContext {
+                revealed_stage: 0,
+                unsolved_sec: 0,
+                treasury_fee_bps,
+                difficulty_bps: BASIS_POINTS as u32,
+                streak_bps: BASIS_POINTS as u32,
+                credit_bps: BASIS_POINTS as u32,
+                hint_penalty_bps: 0,
+                time_decay_bps: BASIS_POINTS as u32,
+                account_cap: self.remaining_winnings(&share.account_id, &quiz),
+                pool: Some(quiz.escrow)
+            };
+            let breakdown = compute_payout(&params, share.attempt_number, &context);
+            let solve = SolvedAttempt { attempt_number: share.attempt_number, placement: None, credit_bps: BASIS_POINTS as u32 };
+            paid += self.record_payout(quiz_id, &mut quiz, &share.account_id, &share.recipient, &solve, breakdown);
+        }
+        self.quizzes.insert(&quiz_id, &quiz);
+
+        emit_event("split_distributed", json!({
+            "quiz_id": quiz_id,
+            "winners": shares.len(),
+            "amount": U128(paid)
+        }));
+
+        U128(paid)
+    }
+
+    pub fn get_split_winners(&self, quiz_id: QuizId) -> Vec<SplitShare> {
+        self.split_winners.get(&quiz_id).map_or_else(Vec::new, |winners| winners.to_vec())
+    }
+
+    pub fn get_nft_prize(&self, quiz_id: QuizId) -> Option<NftPrize> {
+        self.quizzes.get(&quiz_id).and_then(|quiz| quiz.nft_prize)
+    }
+
+    pub fn claim_tokens(&mut self, token_id: AccountId) -> U128 {
+        self.enter_phase(ANY_PHASE);
+        self.check_not_paused();
+
+        let account_id = env::predecessor_account_id();
+        let mut claimable = self.claimable_tokens.get(&account_id).unwrap_or_else(|| panic_json(QuizError::NothingToClaim));
+        let amount = claimable.remove(&token_id).unwrap_or_else(|| panic_json(QuizError::NothingToClaim));
+        self.claimable_tokens.insert(&account_id, &claimable);
+
+        self.transfer_ft(&token_id, &account_id, amount);
+        U128(amount)
+    }
+
+    pub fn get_claimable_tokens(&self, account_id: AccountId, token_id: AccountId) -> U128 {
+        U128(self.claimable_tokens.get(&account_id).and_then(|claimable| claimable.get(&token_id)).unwrap_or(0))
+    }
+
+    // Funders take back their share of what is left, so a partly paid out pool is split pro rata.
+    pub fn refund_quiz(&mut self, quiz_id: QuizId) -> U128 {
+        self.enter_phase(ANY_PHASE);
+        self.check_not_paused();
+
+        let mut quiz = self.expect_quiz(quiz_id);
+        if !quiz.escrow_refundable(self.phase) {
+            panic_json(QuizError::EscrowLocked { quiz_id });
+        }
+
+        let account_id = env::predecessor_account_id();
+        let mut funders = self.quiz_funders.get(&quiz_id).unwrap_or_else(|| panic_json(QuizError::NothingToRefund));
+        let contributed = funders.get(&account_id).filter(|_| quiz.escrow > 0).unwrap_or_else(|| panic_json(QuizError::NothingToRefund));
+
+        let refund = self.refund_share(quiz_id, &mut quiz, &account_id, contributed);
+        self.quizzes.insert(&quiz_id, &quiz);
+        funders.remove(&account_id);
+        self.quiz_funders.insert(&quiz_id, &funders);
+
+        U128(refund)
+    }
+
+    pub fn get_quiz(&self, quiz_id: QuizId) -> Option<JsonQuiz> {
+        self.quizzes.get(&quiz_id).filter(|quiz| quiz.has_gone_live()).map(|quiz| quiz.to_json_quiz(quiz_id))
+    }
+
+    pub fn get_quiz_funding(&self, quiz_id: QuizId) -> Option<QuizFunding> {
+        let quiz = self.quizzes.get(&quiz_id)?;
+        Some(QuizFunding {
+            balance: U128(quiz.escrow),
+            total_funded: U128(quiz.funded),
+            funders: self.quiz_funders.get(&quiz_id).map_or(0, |funders| funders.len())
+        })
+    }
+
+    pub fn get_stats(&self) -> ContractStats {
+        ContractStats {
+            total_quizzes: self.current_quiz_id,
+            published_quizzes: self.published_quiz_ids.len(),
+            total_attempts: self.total_attempts,
+            total_solves: self.total_solves,
+            unique_solvers: self.unique_solvers,
+            near_distributed: U128(self.near_distributed)
+        }
+    }
+
+    pub fn get_quiz_stats(&self, quiz_id: QuizId) -> Option<QuizStats> {
+        let quiz = self.quizzes.get(&quiz_id)?;
+        Some(QuizStats {
+            attempts: quiz.total_attempts,
+            practice_attempts: quiz.practice_attempts,
+            participants: self.quiz_participants.get(&quiz_id).map_or(0, |participants| participants.len()),
+            solver_count: quiz.solver_count,
+            winner_count: quiz.winner_count,
+            paid_out: U128(quiz.paid_out)
+        })
+    }
+
+    pub fn get_quiz_budget(&self, quiz_id: QuizId) -> Option<QuizBudget> {
+        let quiz = self.quizzes.get(&quiz_id)?;
+        let pending = if self.split_winners.get(&quiz_id).is_some_and(|winners| !winners.is_empty()) { quiz.escrow } else { 0 };
+        Some(QuizBudget {
+            token: quiz.prize_token.clone(),
+            funded: U128(quiz.escrow + quiz.paid_out),
+            paid_out: U128(quiz.paid_out),
+            pending_claims: U128(pending),
+            remaining: U128(quiz.escrow - pending)
+        })
+    }
+
+    // Funders who have not refunded an expired, unsolved quiz by now forfeit their share to the jackpot.
+    pub fn roll_into_jackpot(&mut self, quiz_id: QuizId) -> U128 {
+        self.enter_phase(ANY_PHASE);
+        self.check_owner();
+
+        let mut quiz = self.expect_quiz(quiz_id);
+        assert!(quiz.is_expired() && quiz.solver_count == 0, "Only expired quizzes nobody solved roll into the jackpot");
+        assert!(quiz.prize_token.is_none(), "The jackpot only holds NEAR");
+        if quiz.escrow == 0 {
+            panic_json(QuizError::NothingToRefund);
+        }
+
+        let amount = quiz.escrow;
+        quiz.escrow = 0;
+        quiz.funded = 0;
+        self.quizzes.insert(&quiz_id, &quiz);
+        if let Some(mut funders) = self.quiz_funders.remove(&quiz_id) {
+            funders.clear();
+        }
+        self.jackpot += amount;
+
+        emit_event("jackpot_rolled", json!({
+            "quiz_id": quiz_id,
+            "amount": U128(amount),
+            "jackpot": U128(self.jackpot)
+        }));
+
+        U128(self.jackpot)
+    }
+
+    // The jackpot goes on top of the prize of a single-winner quiz. It is booked as the contract's own
+    // funding, so it rolls back in if the quiz expires unsolved again.
+    pub fn attach_jackpot(&mut self, quiz_id: QuizId, amount: Option<U128>) -> U128 {
+        self.enter_phase(ACTIVE_ONLY);
+        self.check_owner();
+
+        let mut quiz = self.expect_quiz(quiz_id);
+        assert!(quiz.prize_token.is_none(), "The jackpot only holds NEAR");
+        assert!(
+            quiz.placements.is_empty() && quiz.prize_split.is_none() && quiz.max_winners.unwrap_or(1) == 1,
+            "A jackpot can only be attached to a single-winner quiz"
+        );
+        let amount = amount.map_or(self.jackpot, |amount| amount.0);
+        assert!(amount > 0 && amount <= self.jackpot, "The jackpot holds {} yoctoNEAR", self.jackpot);
+
+        self.jackpot -= amount;
+        quiz.max_prize_amount += amount;
+        let balance = self.add_funding(quiz_id, quiz, &env::current_account_id(), amount);
+
+        emit_event("jackpot_attached", json!({
+            "quiz_id": quiz_id,
+            "amount": U128(amount),
+            "jackpot": U128(self.jackpot)
+        }));
+
+        U128(balance)
+    }
+
+    pub fn get_jackpot(&self) -> U128 {
+        U128(self.jackpot)
+    }
+
+    // Deleted ids fail with ERR_QUIZ_DELETED so they can be told apart from ids that never existed.
+    pub fn get_quiz_status(&self, quiz_id: QuizId) -> Option<QuizStatus> {
+        if self.deleted_quiz_ids.contains(&quiz_id) {
+            panic_json(QuizError::QuizDeleted { quiz_id });
+        }
+
+        self.quizzes.get(&quiz_id).map(|quiz| quiz.status)
+    }
+
+    pub fn publish_quiz(&mut self, quiz_id: QuizId) {
+        self.enter_phase(ACTIVE_ONLY);
+        self.check_owner();
+
+        let mut quiz = self.expect_quiz(quiz_id);
+        if quiz.status == QuizStatus::Unpublished {
+            quiz.status = QuizStatus::Published;
+            if quiz.epoch_locked {
+                quiz.epoch_height = Some(env::epoch_height());
+            }
+            quiz.published_at = Some(env::block_timestamp());
+            self.list_quiz(quiz_id, &quiz);
+            self.record_activity(quiz_id, ActivityKind::Published);
+            self.record_transition(quiz_id, QuizTransition::Published);
+
+            if let Some(mut reviewers) = self.draft_reviewers.remove(&quiz_id) {
+                reviewers.clear();
+            }
+        } else if quiz.status == QuizStatus::Published && !quiz.is_live() {
+            quiz.published_at = Some(env::block_timestamp());
+        }
+
+        self.quizzes.insert(&quiz_id, &quiz);
+    }
+
+    // Raising the prize above what is escrowed needs the difference attached, the same as create_quiz.
+    #[payable]
+    pub fn update_quiz(&mut self, quiz_id: QuizId, question: Option<String>, correct_hashes: Option<Vec<String>>, max_prize_amount: Option<U128>) {
+        self.enter_phase(ACTIVE_ONLY);
+        self.check_owner();
+
+        let mut quiz = self.expect_quiz(quiz_id);
+        assert!(quiz.status != QuizStatus::Archived, "Archived quizzes cannot be edited");
+        assert!(!quiz.ever_attempted, "Quiz can no longer be edited once it has been attempted");
+
+        if let Some(question) = question {
+            assert!(quiz.question_parts.is_empty(), "Pass either a question or question_parts, not both");
+            self.unindex_question(quiz_id, &quiz.question);
+            self.index_question(quiz_id, &question, false);
+            quiz.question = question;
+        }
+        if let Some(correct_hashes) = correct_hashes {
+            assert!(quiz.kind == QuizKind::FreeText, "Only free-text quizzes take new correct_hashes");
+            assert!(!correct_hashes.is_empty(), "Provide at least one correct hash");
+            quiz.correct_hashes = correct_hashes;
+        }
+        if let Some(max_prize_amount) = max_prize_amount {
+            quiz.max_prize_amount = max_prize_amount.0;
+        }
+
+        let shortfall = quiz.near_budget().saturating_sub(quiz.escrow);
+        let deposit = env::attached_deposit();
+        if deposit < shortfall {
+            panic_json(QuizError::InsufficientDeposit { purpose: DepositPurpose::Escrow, required: U128(shortfall), attached: U128(deposit) });
+        }
+        if deposit > 0 {
+            self.add_funding(quiz_id, quiz, &env::predecessor_account_id(), deposit);
+        } else {
+            self.quizzes.insert(&quiz_id, &quiz);
+        }
+    }
+
+    pub fn extend_deadline(&mut self, quiz_id: QuizId, expires_at: u64) {
+        self.enter_phase(ACCEPTING_ANSWERS);
+        self.check_owner();
+
+        let mut quiz = self.expect_quiz(quiz_id);
+        let current = quiz.expires_at.expect("This quiz has no deadline");
+        assert!(!quiz.is_expired(), "This quiz has already expired");
+        assert!(expires_at > current, "A deadline can only be extended");
+
+        quiz.expires_at = Some(expires_at);
+        self.quizzes.insert(&quiz_id, &quiz);
+    }
+
+    pub fn unpublish_quiz(&mut self, quiz_id: QuizId) {
+        self.enter_phase(ANY_PHASE);
+        self.check_owner();
+
+        let mut quiz = self.expect_quiz(quiz_id);
+        if quiz.status == QuizStatus::Published {
+            quiz.status = QuizStatus::Unpublished;
+            self.unlist_quiz(quiz_id, &quiz);
+            self.record_transition(quiz_id, QuizTransition::Unpublished);
+            self.quizzes.insert(&quiz_id, &quiz);
+        }
+    }
+
+    pub fn correct_answer(&mut self, quiz_id: QuizId, new_hashes: Vec<String>) -> Vec<AccountId> {
+        self.enter_phase(ACTIVE_ONLY);
+        self.check_owner();
+
+        let mut quiz = self.expect_quiz(quiz_id);
+        assert!(quiz.kind == QuizKind::FreeText, "Only free-text quizzes take new correct_hashes");
+        assert!(!new_hashes.is_empty(), "Provide at least one correct hash");
+        assert!(new_hashes != quiz.correct_hashes, "These are already the correct answers");
+
+        let previous_hashes = std::mem::replace(&mut quiz.correct_hashes, new_hashes);
+        quiz.previous_hashes.extend(previous_hashes.iter().cloned());
+        self.quizzes.insert(&quiz_id, &quiz);
+
+        let participants = self.quiz_participants.get(&quiz_id).map_or_else(Vec::new, |participants| participants.to_vec());
+        let mut reset_accounts = vec![];
+        for account_id in participants {
+            let (solved, retries_left) = self.participation(&account_id, quiz_id, &quiz);
+            if solved || retries_left > 0 {
+                continue;
+            }
+
+            let mut records = self.retries_left.get(&account_id).unwrap_or_else(|| {
+                LookupMap::new(account_prefix(b'u', &account_id))
+            });
+            let record = self.retry_record(&account_id, quiz_id, &quiz);
+            records.insert(&quiz_id, &RetryRecord { retries_left: quiz.max_retries, insurance: None, last_failed_at: None, ..record });
+            self.retries_left.insert(&account_id, &records);
+            reset_accounts.push(account_id);
+        }
+
+        emit_event("quiz_answer_corrected", json!({
+            "quiz_id": quiz_id,
+            "previous_hashes": previous_hashes,
+            "reset_accounts": reset_accounts
+        }));
+
+        reset_accounts
+    }
+
+    pub fn close_quiz(&mut self, quiz_id: QuizId) {
+        self.enter_phase(ANY_PHASE);
+        self.check_owner();
+
+        let mut quiz = self.expect_quiz(quiz_id);
+        assert!(quiz.status == QuizStatus::Published, "Only published quizzes can be closed");
+
+        quiz.status = QuizStatus::Closed;
+        self.unlist_quiz(quiz_id, &quiz);
+        self.record_transition(quiz_id, QuizTransition::Closed);
+
+        // A split prize is still owed to its winners, so its escrow waits for distribute_split.
+        let split_pending = self.split_winners.get(&quiz_id).is_some_and(|winners| !winners.is_empty());
+        let mut refunded = 0;
+        if !split_pending && quiz.escrow > 0 {
+            if let Some(mut funders) = self.quiz_funders.remove(&quiz_id) {
+                for (account_id, contributed) in funders.to_vec() {
+                    refunded += self.refund_share(quiz_id, &mut quiz, &account_id, contributed);
+                }
+                funders.clear();
+            }
+        }
+        self.quizzes.insert(&quiz_id, &quiz);
+        self.record_activity(quiz_id, ActivityKind::Expired);
+
+        emit_event("quiz_closed", json!({
+            "quiz_id": quiz_id,
+            "solver_count": quiz.solver_count,
+            "refunded": U128(refunded)
+        }));
+    }
+
+    pub fn archive_quiz(&mut self, quiz_id: QuizId) {
+        self.enter_phase(ANY_PHASE);
+        self.check_owner();
+
+        let mut quiz = self.expect_quiz(quiz_id);
+        if quiz.status != QuizStatus::Archived {
+            quiz.status = QuizStatus::Archived;
+            self.unlist_quiz(quiz_id, &quiz);
+            self.record_transition(quiz_id, QuizTransition::Archived);
+            self.quizzes.insert(&quiz_id, &quiz);
+
+            if let Some(mut reviewers) = self.draft_reviewers.remove(&quiz_id) {
+                reviewers.clear();
+            }
+        }
+    }
+
+    pub fn reopen_quiz(&mut self, quiz_id: QuizId) -> u32 {
+        self.enter_phase(ACTIVE_ONLY);
+        self.check_owner();
+
+        let mut quiz = self.expect_quiz(quiz_id);
+        assert!(
+            matches!(quiz.status, QuizStatus::Published | QuizStatus::Closed),
+            "Only published or closed quizzes can be reopened"
+        );
+        assert!(self.split_winners.get(&quiz_id).is_none(), "Distribute the split prize before reopening the quiz");
+
+        if quiz.status == QuizStatus::Closed {
+            quiz.status = QuizStatus::Published;
+            self.list_quiz(quiz_id, &quiz);
+        }
+        quiz.start_round();
+
+        self.quizzes.insert(&quiz_id, &quiz);
+        self.record_activity(quiz_id, ActivityKind::Published);
+        self.record_transition(quiz_id, QuizTransition::Reopened);
+
+        emit_event("quiz_reopened", json!({
+            "quiz_id": quiz_id,
+            "round": quiz.round
+        }));
+
+        quiz.round
+    }
+
+    pub fn roll_epoch_quiz(&mut self, quiz_id: QuizId, new_hash: String, new_question: String) {
+        self.enter_phase(ACTIVE_ONLY);
+        self.check_owner();
+
+        let mut quiz = self.expect_quiz(quiz_id);
+        assert!(quiz.epoch_locked, "Only epoch-locked quizzes can be rolled");
+        assert!(quiz.status == QuizStatus::Published, "Only published quizzes can be rolled");
+
+        self.unindex_question(quiz_id, &quiz.question);
+        self.index_question(quiz_id, &new_question, false);
+
+        quiz.question = new_question;
+        quiz.correct_hashes = vec![new_hash];
+        quiz.start_round();
+
+        self.quizzes.insert(&quiz_id, &quiz);
+        self.record_activity(quiz_id, ActivityKind::Expired);
+    }
+
+    pub fn share_draft(&mut self, quiz_id: QuizId, reviewer: AccountId) {
+        self.enter_phase(ACTIVE_ONLY);
+        self.check_owner();
+
+        let quiz = self.expect_quiz(quiz_id);
+        assert!(quiz.status == QuizStatus::Unpublished, "Cannot share a published quiz");
+
+        let mut reviewers = self.draft_reviewers.get(&quiz_id).unwrap_or_else(|| {
+            UnorderedSet::new(quiz_prefix(b'd', quiz_id))
+        });
+
+        reviewers.insert(&reviewer);
+        self.draft_reviewers.insert(&quiz_id, &reviewers);
+    }
+
+    pub fn revoke_draft_share(&mut self, quiz_id: QuizId, reviewer: AccountId) {
+        self.enter_phase(ANY_PHASE);
+        self.check_owner();
+
+        let mut reviewers = self.draft_reviewers.get(&quiz_id).expect("This draft is not shared with anyone");
+        assert!(reviewers.remove(&reviewer), "This draft is not shared with this account");
+
+        if reviewers.is_empty() {
+            self.draft_reviewers.remove(&quiz_id);
+        } else {
+            self.draft_reviewers.insert(&quiz_id, &reviewers);
+        }
+    }
+
+    // Has to be sent as a transaction since view calls have no predecessor to check against.
+    pub fn get_shared_draft(&self, quiz_id: QuizId) -> JsonQuizDraft {
+        let account_id = env::predecessor_account_id();
+        let is_reviewer = self.draft_reviewers.get(&quiz_id).is_some_and(|reviewers| reviewers.contains(&account_id));
+        assert!(is_reviewer, "This draft is not shared with you");
+
+        self.expect_quiz(quiz_id).to_json_draft(quiz_id)
+    }
+
+    // Lists drafts too. Contract state is public whatever a view returns, so this is no secret.
+    pub fn get_all_quizzes(&self, from_index: QuizId, limit: u64) -> Vec<JsonQuizDraft> {
+        self.quiz_drafts(from_index, limit, None)
+    }
+
+    // Pages over quiz ids like get_all_quizzes, so a page holds at most limit drafts. Drafts are
+    // public state like everything else, so this is a convenience for the owner, not a secret.
+    pub fn get_unpublished_quizzes(&self, from_index: QuizId, limit: u64) -> Vec<JsonQuizDraft> {
+        self.quiz_drafts(from_index, limit, Some(QuizStatus::Unpublished))
+    }
+
+    pub fn get_quiz_history(&self, quiz_id: QuizId) -> Vec<StatusChange> {
+        self.quiz_history.get(&quiz_id).map_or_else(Vec::new, |history| history.to_vec())
+    }
+
+    pub fn get_quiz_page(&self, quiz_id: QuizId, viewer: Option<AccountId>) -> Option<QuizPage> {
+        let quiz = self.quizzes.get(&quiz_id)?;
+        if !quiz.is_live() {
+            return None;
+        }
+
+        let participation = viewer.as_ref().map(|viewer| self.participation(viewer, quiz_id, &quiz));
+        let eligible = viewer.map(|viewer| self.missing_prerequisites(&viewer, &quiz).is_empty());
+
+        Some(QuizPage {
+            quiz_id,
+            question: quiz.visible_question(),
+            prize_amount: U128(quiz.effective_prize()),
+            stale: quiz.is_stale(),
+            epoch_height: quiz.epoch_height,
+            solver_count: quiz.solver_count,
+            practice_attempts: quiz.practice_attempts,
+            global_attempts_remaining: quiz.global_attempts_remaining(),
+            retries_left: participation.map(|(_, retries_left)| retries_left),
+            solved: participation.map(|(solved, _)| solved),
+            time_remaining_sec: quiz.time_remaining_sec(),
+            eligible,
+            status: quiz.status
+        })
+    }
+
+    pub fn get_next_attempt_fee(&self, quiz_id: QuizId, account_id: AccountId) -> U128 {
+        let quiz = self.expect_quiz(quiz_id);
+        let (solved, retries_left) = self.participation(&account_id, quiz_id, &quiz);
+        if solved || retries_left == 0 {
+            return U128(0);
+        }
+
+        U128(self.attempt_fee(&account_id, &quiz, retries_left))
+    }
+
+    pub fn get_payout_records(&self, from_index: u64, limit: u64) -> Vec<PayoutRecord> {
+        (from_index..self.payout_records.len().min(from_index.saturating_add(limit)))
+            .filter_map(|index| self.payout_records.get(index))
+            .collect()
+    }
+
+    pub fn get_payouts_for(&self, account_id: AccountId, from_index: u64, limit: u64) -> Vec<PayoutRecord> {
+        let account_payouts = match self.account_payouts.get(&account_id) {
+            Some(account_payouts) => account_payouts,
+            None => return vec![]
+        };
+
+        (from_index..account_payouts.len().min(from_index.saturating_add(limit)))
+            .filter_map(|index| account_payouts.get(index))
+            .filter_map(|record_index| self.payout_records.get(record_index))
+            .collect()
+    }
+
+    pub fn get_leaderboard(&self, limit: u64) -> Vec<LeaderboardEntry> {
+        self.leaderboard.iter().take(limit as usize).cloned().collect()
+    }
+
+    pub fn get_account_stats(&self, account_id: AccountId) -> AccountStats {
+        let tally = self.account_tallies.get(&account_id).unwrap_or_default();
+        AccountStats {
+            solved: tally.solved,
+            attempts: tally.attempts,
+            winnings: U128(self.account_winnings(&account_id).total),
+            streak: self.get_streak(account_id.clone()),
+            rank: self.leaderboard.iter().position(|entry| entry.account_id == account_id).map(|index| index as u32 + 1)
+        }
+    }
+
+    pub fn get_solved_quizzes(&self, account_id: AccountId, from_index: u64, limit: u64) -> Vec<QuizId> {
+        let Some(solved_quizzes) = self.solved_quizzes.get(&account_id) else {
+            return vec![];
+        };
+
+        let quiz_ids = solved_quizzes.as_vector();
+        (from_index..quiz_ids.len().min(from_index.saturating_add(limit)))
+            .filter_map(|index| quiz_ids.get(index))
+            .collect()
+    }
+
+    pub fn grade_submission(&mut self, quiz_id: QuizId, account_id: AccountId, accepted: bool, reason: Option<String>) -> SubmitResult {
+        self.enter_phase(ACCEPTING_ANSWERS);
+        self.check_not_paused();
+        let grader = env::predecessor_account_id();
+        if grader != self.owner_id && !self.graders.contains(&grader) {
+            panic_json(QuizError::NotGrader { account_id: grader });
+        }
+
+        let mut quiz = self.expect_quiz(quiz_id);
+        let mut submission = self.pending_submission(&account_id, quiz_id, &quiz).expect("No pending submission from this account");
+        submission.status = if accepted { ReviewStatus::Accepted } else { ReviewStatus::Rejected { reason: reason.clone() } };
+        let mut submissions = self.submissions.get(&account_id).unwrap();
+        submissions.insert(&quiz_id, &submission);
+        self.submissions.insert(&account_id, &submissions);
+
+        let mut queue = self.review_queues.get(&quiz_id).unwrap();
+        queue.remove(&account_id);
+        if queue.is_empty() {
+            self.review_queues.remove(&quiz_id);
+        } else {
+            self.review_queues.insert(&quiz_id, &queue);
+        }
+
+        emit_event("submission_graded", json!({
+            "quiz_id": quiz_id,
+            "account_id": account_id,
+            "grader": grader,
+            "accepted": accepted,
+            "reason": reason
+        }));
+
+        self.record_attempt(&account_id, quiz_id, quiz.round, accepted);
+        let record = self.retry_record(&account_id, quiz_id, &quiz);
+        if accepted {
+            return self.reward_solver(&account_id, quiz_id, quiz, record, submission.payout_to, (1, 1));
+        }
+
+        let retries_left = self.record_miss(&account_id, quiz_id, &mut quiz, record);
+        let message = match reason {
+            Some(reason) => format!("The answer was rejected: {}", reason),
+            None => "The answer was rejected".to_owned()
+        };
+
+        SubmitResult { correct: false, message, amount: None, retries_left, feedback: None, prize_pool_exhausted: false }
+    }
+
+    pub fn get_review_queue(&self, quiz_id: QuizId, from_index: u64, limit: u64) -> Vec<PendingSubmission> {
+        let Some(queue) = self.review_queues.get(&quiz_id) else {
+            return vec![];
+        };
+
+        queue.iter()
+            .skip(from_index as usize)
+            .take(limit as usize)
+            .filter_map(|account_id| {
+                let submission = self.submissions.get(&account_id)?.get(&quiz_id)?;
+                Some(PendingSubmission { account_id, answer: submission.answer, submitted_at: submission.submitted_at })
+            })
+            .collect()
+    }
+
+    pub fn get_submission(&self, account_id: AccountId, quiz_id: QuizId) -> Option<Submission> {
+        self.submissions.get(&account_id).and_then(|submissions| submissions.get(&quiz_id))
+    }
+
+    pub fn add_grader(&mut self, account_id: AccountId) {
+        self.enter_phase(ANY_PHASE);
+        self.check_owner();
+
+        self.graders.insert(&account_id);
+    }
+
+    pub fn remove_grader(&mut self, account_id: AccountId) {
+        self.enter_phase(ANY_PHASE);
+        self.check_owner();
+
+        assert!(self.graders.remove(&account_id), "This account is not a grader");
+    }
+
+    pub fn get_first_solver(&self, quiz_id: QuizId) -> Option<AccountId> {
+        self.quizzes.get(&quiz_id).and_then(|quiz| quiz.first_solver)
+    }
+
+    pub fn get_current_step(&self, quiz_id: QuizId, account_id: AccountId) -> Option<JsonStep> {
+        let quiz = self.quizzes.get(&quiz_id)?;
+        let QuizKind::Sequence { steps } = &quiz.kind else {
+            return None;
+        };
+
+        let step = self.retry_record(&account_id, quiz_id, &quiz).step;
+        Some(JsonStep { step, steps: steps.len(), question: quiz.step_question(step) })
+    }
+
+    pub fn get_retries_left(&self, account_id: AccountId, quiz_id: QuizId) -> Option<usize> {
+        let quiz = self.quizzes.get(&quiz_id)?;
+        Some(self.participation(&account_id, quiz_id, &quiz).1)
+    }
+
+    pub fn get_attempt_history(&self, account_id: AccountId, quiz_id: QuizId, from_index: u64, limit: u64) -> Vec<AttemptRecord> {
+        let Some(attempts) = self.attempt_history.get(&account_id).and_then(|history| history.get(&quiz_id)) else {
+            return vec![];
+        };
+
+        (from_index..attempts.len().min(from_index.saturating_add(limit)))
+            .filter_map(|index| attempts.get(index))
+            .collect()
+    }
+
+    pub fn set_season_pass_config(&mut self, price: U128, duration_sec: u64, season_ends_at: Option<u64>) {
+        self.enter_phase(ACTIVE_ONLY);
+        self.check_owner();
+
+        self.season_pass_config = Some(SeasonPassConfig {
+            price,
+            duration_sec,
+            season_ends_at
+        });
+    }
+
+    pub fn roll_season(&mut self, season_ends_at: Option<u64>) {
+        self.enter_phase(ACTIVE_ONLY);
+        self.check_owner();
+
+        let mut config = self.season_pass_config.take().expect("Season passes are not configured");
+        config.season_ends_at = season_ends_at;
+        self.season_pass_config = Some(config);
+        self.season += 1;
+    }
+
+    pub fn get_season_pass_config(&self) -> Option<SeasonPassConfig> {
+        self.season_pass_config.clone()
+    }
+
+    #[payable]
+    pub fn buy_season_pass(&mut self) -> u64 {
+        self.enter_phase(ACTIVE_ONLY);
+        let config = self.season_pass_config.as_ref().expect("Season passes are not on sale");
+        let price = config.price.0;
+        let deposit = env::attached_deposit();
+        if deposit < price {
+            panic_json(QuizError::InsufficientDeposit { purpose: DepositPurpose::SeasonPass, required: U128(price), attached: U128(deposit) });
+        }
+
+        let now = env::block_timestamp();
+        let account_id = env::predecessor_account_id();
+        let starts_at = self.has_season_pass(account_id.clone()).unwrap_or(now);
+
+        let mut expires_at = starts_at + config.duration_sec * NANOS_PER_SEC;
+        if let Some(season_ends_at) = config.season_ends_at {
+            expires_at = expires_at.min(season_ends_at);
+        }
+        assert!(expires_at > starts_at, "The current season is over");
+
+        self.season_passes.insert(&account_id, &SeasonPass { season: self.season, expires_at });
+        self.withdrawable_balance += price;
+
+        if deposit > price {
+            Promise::new(account_id).transfer(deposit - price);
+        }
+
+        expires_at
+    }
+
+    pub fn has_season_pass(&self, account_id: AccountId) -> Option<u64> {
+        self.season_passes
+            .get(&account_id)
+            .filter(|pass| pass.season == self.season && pass.expires_at > env::block_timestamp())
+            .map(|pass| pass.expires_at)
+    }
+
+    pub fn get_withdrawable_balance(&self) -> U128 {
+        U128(self.withdrawable_balance)
+    }
+
+    pub fn withdraw_balance(&mut self) -> U128 {
+        self.enter_phase(ANY_PHASE);
+        self.check_owner();
+        self.check_not_paused();
+
+        let amount = self.withdrawable_balance;
+        if amount == 0 {
+            panic_json(QuizError::NothingToWithdraw);
+        }
+        self.withdrawable_balance = 0;
+
+        Promise::new(self.owner_id.clone()).transfer(amount);
+
+        U128(amount)
+    }
+
+    pub fn set_treasury(&mut self, account_id: AccountId, fee_bps: u16) {
+        self.enter_phase(ACCEPTING_ANSWERS);
+        self.check_owner();
+        if fee_bps > MAX_TREASURY_FEE_BPS {
+            panic_json(QuizError::TreasuryFeeTooHigh { fee_bps, max_bps: MAX_TREASURY_FEE_BPS });
+        }
+
+        self.treasury = Some(TreasuryConfig { account_id, fee_bps });
+    }
+
+    pub fn set_fee(&mut self, fee_bps: u16) {
+        self.enter_phase(ACCEPTING_ANSWERS);
+        self.check_owner();
+        if fee_bps > MAX_TREASURY_FEE_BPS {
+            panic_json(QuizError::TreasuryFeeTooHigh { fee_bps, max_bps: MAX_TREASURY_FEE_BPS });
+        }
+
+        self.treasury.as_mut().expect("Set a treasury account first").fee_bps = fee_bps;
+    }
+
+    pub fn get_treasury(&self) -> Option<TreasuryConfig> {
+        self.treasury.clone()
+    }
+
+    // Fees accrue in the contract and are only sent out here, to whichever account is the treasury now.
+    pub fn withdraw_treasury(&mut self, token_id: Option<AccountId>) -> U128 {
+        self.enter_phase(ANY_PHASE);
+        let treasury = self.treasury.as_ref().expect("No treasury is set").account_id.clone();
+        if env::predecessor_account_id() != treasury {
+            self.check_owner();
+        }
+
+        let amount = match &token_id {
+            Some(token) => self.accrued_token_fees.remove(token).unwrap_or(0),
+            None => std::mem::take(&mut self.accrued_fees)
+        };
+        if amount == 0 {
+            panic_json(QuizError::NothingToWithdraw);
+        }
+        match &token_id {
+            Some(token) => self.transfer_ft(token, &treasury, amount),
+            None => self.transfer_near(&treasury, amount)
+        }
+
+        emit_event("treasury_withdrawn", json!({
+            "account_id": treasury,
+            "token": token_id,
+            "amount": U128(amount)
+        }));
+
+        U128(amount)
+    }
+
+    pub fn get_accrued_fees(&self) -> AccruedFees {
+        AccruedFees {
+            near: U128(self.accrued_fees),
+            tokens: self.accrued_token_fees.iter().map(|(token, amount)| (token, U128(amount))).collect()
+        }
+    }
+
+    pub fn set_difficulty_multipliers(&mut self, multipliers: DifficultyMultipliers) {
+        self.enter_phase(ACTIVE_ONLY);
+        self.check_owner();
+
+        self.difficulty_multipliers = multipliers;
+    }
+
+    pub fn get_difficulty_multipliers(&self) -> DifficultyMultipliers {
+        self.difficulty_multipliers.clone()
+    }
+
+    pub fn set_streak_config(&mut self, config: StreakConfig) {
+        self.enter_phase(ACTIVE_ONLY);
+        self.check_owner();
+
+        self.streak_config = config;
+    }
+
+    pub fn get_streak_config(&self) -> StreakConfig {
+        self.streak_config.clone()
+    }
+
+    pub fn set_referral_config(&mut self, config: ReferralConfig) {
+        self.enter_phase(ACTIVE_ONLY);
+        self.check_owner();
+
+        assert!(config.bonus_bps as u128 <= BASIS_POINTS, "bonus_bps cannot exceed {}", BASIS_POINTS);
+        self.referral_config = config;
+    }
+
+    pub fn get_referral_config(&self) -> ReferralConfig {
+        self.referral_config.clone()
+    }
+
+    // Only accounts that have not won anything yet can name a referrer, and only once.
+    pub fn register_referral(&mut self, referrer: AccountId) {
+        self.enter_phase(ACTIVE_ONLY);
+        self.check_not_paused();
+
+        let account_id = env::predecessor_account_id();
+        assert!(referrer != account_id, "You cannot refer yourself");
+        assert!(!self.referrals.contains_key(&account_id), "A referrer is already registered for this account");
+        assert!(
+            self.referrals.get(&referrer).is_none_or(|referral| referral.referrer != account_id),
+            "{} was referred by you", referrer
+        );
+        assert!(!self.account_payouts.contains_key(&account_id), "Referrals must be registered before the first prize");
+
+        self.referrals.insert(&account_id, &Referral { referrer: referrer.clone(), rewarded_wins: 0 });
+        let mut stats = self.get_referrer_stats(referrer.clone());
+        stats.referred += 1;
+        self.referrers.insert(&referrer, &stats);
+
+        emit_event("referral_registered", json!({
+            "account_id": account_id,
+            "referrer": referrer
+        }));
+    }
+
+    pub fn get_referral(&self, account_id: AccountId) -> Option<Referral> {
+        self.referrals.get(&account_id)
+    }
+
+    pub fn get_referrer_stats(&self, account_id: AccountId) -> ReferrerStats {
+        self.referrers.get(&account_id).unwrap_or(ReferrerStats { referred: 0, earned: U128(0) })
+    }
+
+    pub fn set_payout_cap(&mut self, cap: Option<PayoutCap>) {
+        self.enter_phase(ANY_PHASE);
+        self.check_owner();
+
+        self.payout_cap = cap;
+    }
+
+    pub fn get_payout_cap(&self) -> Option<PayoutCap> {
+        self.payout_cap.clone()
+    }
+
+    pub fn get_winnings(&self, account_id: AccountId) -> Winnings {
+        let winnings = self.account_winnings(&account_id);
+        let won = |cap: &PayoutCap| if cap.per_season { winnings.season_total } else { winnings.total };
+        Winnings {
+            total: U128(winnings.total),
+            season_total: U128(winnings.season_total),
+            remaining: self.payout_cap.as_ref().map(|cap| U128(cap.amount.0.saturating_sub(won(cap))))
+        }
+    }
+
+    pub fn get_streak(&self, account_id: AccountId) -> Streak {
+        self.streaks.get(&account_id).unwrap_or_default()
+    }
+
+    pub fn set_default_retries(&mut self, retries: usize) {
+        self.enter_phase(ANY_PHASE);
+        self.check_owner();
+        assert!((1..=MAX_RETRIES).contains(&retries), "max_retries must be between 1 and {}", MAX_RETRIES);
+
+        self.default_retries = retries;
+    }
+
+    pub fn get_default_retries(&self) -> usize {
+        self.default_retries
+    }
+
+    pub fn set_payout_preference(&mut self, min_transfer: U128) {
+        self.enter_phase(ANY_PHASE);
+        let account_id = env::predecessor_account_id();
+        if min_transfer.0 == 0 {
+            self.payout_preferences.remove(&account_id);
+        } else {
+            self.payout_preferences.insert(&account_id, &min_transfer.0);
+        }
+    }
+
+    // With pull payouts on, prizes are only credited when graded and move when their owner calls
+    // claim_prizes or claim_tokens, so a failing transfer can never hold up a submission.
+    pub fn set_pull_payouts(&mut self, enabled: bool) {
+        self.enter_phase(ANY_PHASE);
+        self.check_owner();
+
+        self.pull_payouts = enabled;
+    }
+
+    pub fn get_pull_payouts(&self) -> bool {
+        self.pull_payouts
+    }
+
+    pub fn get_payout_preference(&self, account_id: AccountId) -> PayoutPreference {
+        PayoutPreference {
+            min_transfer: U128(self.payout_preferences.get(&account_id).unwrap_or(0)),
+            accrued: U128(self.claimable_prizes.get(&account_id).unwrap_or(0))
+        }
+    }
+
+    // Also the retry path for prize transfers that failed, see on_prize_transfer.
+    pub fn claim_prizes(&mut self) -> U128 {
+        self.enter_phase(ANY_PHASE);
+        self.check_not_paused();
+        let account_id = env::predecessor_account_id();
+        let amount = self.claimable_prizes.remove(&account_id).unwrap_or_else(|| panic_json(QuizError::NothingToClaim));
+
+        self.transfer_near(&account_id, amount);
+
+        U128(amount)
+    }
+
+    // Sends everything unlocked so far across all of the caller's grants; fully claimed grants are dropped.
+    pub fn claim_vested(&mut self) -> U128 {
+        self.enter_phase(ANY_PHASE);
+        self.check_not_paused();
+        let account_id = env::predecessor_account_id();
+        let mut grants = self.vesting.get(&account_id).unwrap_or_else(|| panic_json(QuizError::NothingToClaim));
+
+        let mut amount = 0;
+        let mut index = grants.len();
+        while index > 0 {
+            index -= 1;
+            let mut grant = grants.get(index).unwrap();
+            amount += grant.unlocked() - grant.claimed;
+            grant.claimed = grant.unlocked();
+            if grant.claimed == grant.total {
+                grants.swap_remove(index);
+            } else {
+                grants.replace(index, &grant);
+            }
+        }
+        if grants.is_empty() {
+            self.vesting.remove(&account_id);
+        } else {
+            self.vesting.insert(&account_id, &grants);
+        }
+        if amount == 0 {
+            panic_json(QuizError::NothingToClaim);
+        }
+
+        self.transfer_near(&account_id, amount);
+        U128(amount)
+    }
+
+    // Fees held for refund come back minus the quiz's rake once it stops taking answers; winners forfeit theirs on solving.
+    pub fn refund_entry(&mut self, quiz_id: QuizId) -> U128 {
+        self.enter_phase(ANY_PHASE);
+        self.check_not_paused();
+
+        let mut quiz = self.expect_quiz(quiz_id);
+        let rake_bps = quiz.entry_refund_rake_bps.expect("This quiz does not refund entry fees");
+        if !quiz.escrow_refundable(self.phase) {
+            panic_json(QuizError::EscrowLocked { quiz_id });
+        }
+        let account_id = env::predecessor_account_id();
+        let fees = self.take_entry_fees(quiz_id, &mut quiz, &account_id).unwrap_or_else(|| panic_json(QuizError::NothingToWithdraw));
+        self.quizzes.insert(&quiz_id, &quiz);
+        let rake = apply_bps(fees, rake_bps as u128);
+        self.withdrawable_balance += rake;
+
+        let refund = fees - rake;
+        if refund > 0 {
+            self.transfer_near(&account_id, refund);
+        }
+
+        emit_event("entry_refunded", json!({
+            "quiz_id": quiz_id,
+            "account_id": account_id,
+            "amount": U128(refund),
+            "rake": U128(rake)
+        }));
+
+        U128(refund)
+    }
+
+    pub fn get_entry_fees(&self, quiz_id: QuizId, account_id: AccountId) -> U128 {
+        U128(self.entry_fees.get(&quiz_id).and_then(|fees| fees.get(&account_id)).unwrap_or(0))
+    }
+
+    // Stakes stay locked while the quiz takes answers and can be slashed by the owner in the meantime.
+    #[payable]
+    pub fn stake_for_quiz(&mut self, quiz_id: QuizId) -> U128 {
+        self.enter_phase(ACCEPTING_ANSWERS);
+        self.check_not_paused();
+
+        let mut quiz = self.expect_quiz(quiz_id);
+        let required = quiz.stake_required.expect("This quiz does not take stakes");
+        let account_id = env::predecessor_account_id();
+        let staked = self.staked(quiz_id, &account_id) + env::attached_deposit();
+        if staked < required {
+            panic_json(QuizError::InsufficientDeposit { purpose: DepositPurpose::Stake, required: U128(required), attached: U128(env::attached_deposit()) });
+        }
+
+        let mut stakes = self.stakes.get(&quiz_id).unwrap_or_else(|| {
+            LookupMap::new(quiz_prefix(b'j', quiz_id))
+        });
+        stakes.insert(&account_id, &staked);
+        self.stakes.insert(&quiz_id, &stakes);
+        quiz.deposits_held += env::attached_deposit();
+        self.quizzes.insert(&quiz_id, &quiz);
+
+        emit_event("stake_locked", json!({
+            "quiz_id": quiz_id,
+            "account_id": account_id,
+            "amount": U128(staked)
+        }));
+
+        U128(staked)
+    }
+
+    pub fn withdraw_stake(&mut self, quiz_id: QuizId) -> U128 {
+        self.enter_phase(ANY_PHASE);
+        self.check_not_paused();
+
+        let mut quiz = self.expect_quiz(quiz_id);
+        if !quiz.escrow_refundable(self.phase) {
+            panic_json(QuizError::EscrowLocked { quiz_id });
+        }
+        let account_id = env::predecessor_account_id();
+        let amount = self.take_stake(quiz_id, &mut quiz, &account_id).unwrap_or_else(|| panic_json(QuizError::NothingToWithdraw));
+        self.quizzes.insert(&quiz_id, &quiz);
+
+        self.transfer_near(&account_id, amount);
+        U128(amount)
+    }
+
+    pub fn slash_stake(&mut self, quiz_id: QuizId, account_id: AccountId, reason: Option<String>) -> U128 {
+        self.enter_phase(ANY_PHASE);
+        self.check_owner();
+
+        let mut quiz = self.expect_quiz(quiz_id);
+        let amount = self.take_stake(quiz_id, &mut quiz, &account_id).unwrap_or_else(|| panic_json(QuizError::NothingToWithdraw));
+        self.quizzes.insert(&quiz_id, &quiz);
+        self.withdrawable_balance += amount;
+
+        emit_event("stake_slashed", json!({
+            "quiz_id": quiz_id,
+            "account_id": account_id,
+            "amount": U128(amount),
+            "reason": reason
+        }));
+
+        U128(amount)
+    }
+
+    pub fn get_stake(&self, quiz_id: QuizId, account_id: AccountId) -> U128 {
+        U128(self.staked(quiz_id, &account_id))
+    }
+
+    // NEP-145 with a flat deposit: min and max are both STORAGE_BALANCE, so nothing is ever available to withdraw.
+    #[payable]
+    pub fn storage_deposit(&mut self, account_id: Option<AccountId>, registration_only: Option<bool>) -> StorageBalance {
+        self.enter_phase(ACCEPTING_ANSWERS);
+        let _ = registration_only;
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+        let deposit = env::attached_deposit();
+
+        if self.storage_deposits.contains_key(&account_id) {
+            if deposit > 0 {
+                Promise::new(env::predecessor_account_id()).transfer(deposit);
+            }
+        } else {
+            if deposit < STORAGE_BALANCE {
+                panic_json(QuizError::InsufficientDeposit { purpose: DepositPurpose::Storage, required: U128(STORAGE_BALANCE), attached: U128(deposit) });
+            }
+            self.storage_deposits.insert(&account_id, &STORAGE_BALANCE);
+            if deposit > STORAGE_BALANCE {
+                Promise::new(env::predecessor_account_id()).transfer(deposit - STORAGE_BALANCE);
+            }
+        }
+
+        self.storage_balance_of(account_id).unwrap()
+    }
+
+    #[payable]
+    pub fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        near_sdk::assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let balance = self.storage_balance_of(account_id.clone()).unwrap_or_else(|| panic_json(QuizError::StorageNotRegistered { account_id }));
+        assert!(amount.is_none_or(|amount| amount.0 == 0), "Nothing is available to withdraw");
+        balance
+    }
+
+    // Unclaimed prizes or vesting grants keep an account registered unless force is set; forcing forfeits nothing, they stay claimable.
+    #[payable]
+    pub fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        near_sdk::assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        if !self.storage_deposits.contains_key(&account_id) {
+            return false;
+        }
+        let pending = self.claimable_prizes.contains_key(&account_id) || self.vesting.contains_key(&account_id);
+        assert!(!pending || force == Some(true), "Claim your prizes first or pass force");
+
+        let deposit = self.storage_deposits.remove(&account_id).unwrap();
+        Promise::new(account_id).transfer(deposit);
+        true
+    }
+
+    pub fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageBalanceBounds { min: U128(STORAGE_BALANCE), max: Some(U128(STORAGE_BALANCE)) }
+    }
+
+    pub fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+        self.storage_deposits.get(&account_id).map(|total| StorageBalance { total: U128(total), available: U128(0) })
+    }
+
+    pub fn set_storage_required(&mut self, required: bool) {
+        self.enter_phase(ANY_PHASE);
+        self.check_owner();
+
+        self.storage_required = required;
+    }
+
+    pub fn get_vesting(&self, account_id: AccountId) -> Vec<JsonVestingGrant> {
+        self.vesting.get(&account_id).map_or_else(Vec::new, |grants| grants.iter().map(|grant| JsonVestingGrant {
+            quiz_id: grant.quiz_id,
+            total: U128(grant.total),
+            claimed: U128(grant.claimed),
+            unlocked: U128(grant.unlocked()),
+            next_unlock_at: grant.next_unlock_at()
+        }).collect())
+    }
+
+    pub fn wager_prize(&mut self, quiz_id: QuizId, follow_up_quiz_id: QuizId) {
+        self.enter_phase(ACCEPTING_ANSWERS);
+        self.check_not_paused();
+        let account_id = env::predecessor_account_id();
+        assert!(self.pending_wagers.get(&account_id).is_none(), "You already have a pending wager");
+        assert!(quiz_id != follow_up_quiz_id, "A prize cannot ride on the quiz that paid it");
+
+        let mut wagers = self.wagers.get(&account_id).unwrap_or_else(|| {
+            LookupMap::new(account_prefix(b'w', &account_id))
+        });
+        assert!(!wagers.contains_key(&quiz_id), "This prize was already wagered");
+
+        let record = self.account_payouts.get(&account_id)
+            .and_then(|payouts| payouts.iter().filter_map(|index| self.payout_records.get(index)).find(|record| record.quiz_id == quiz_id && record.token.is_none()))
+            .filter(|record| record.recipient == account_id)
+            .expect("No prize from this quiz to wager");
+        assert!(env::block_height() <= record.block_height + WAGER_WINDOW_BLOCKS, "The wager window for this prize has closed");
+
+        let amount = record.final_amount.0 - record.treasury_fee.0;
+        let claimable = self.claimable_prizes.get(&account_id).unwrap_or(0);
+        assert!(claimable >= amount, "This prize was already transferred");
+
+        let follow_up = self.expect_quiz(follow_up_quiz_id);
+        assert!(follow_up.accepting_answers(), "The follow-up quiz is not accepting answers");
+        assert!(follow_up.prize_token.is_none(), "Wagers need a follow-up quiz with a NEAR prize");
+        let (solved, retries_left) = self.participation(&account_id, follow_up_quiz_id, &follow_up);
+        assert!(!solved && retries_left == follow_up.max_retries, "Wagers need a follow-up quiz you haven't attempted");
+
+        if claimable == amount {
+            self.claimable_prizes.remove(&account_id);
+        } else {
+            self.claimable_prizes.insert(&account_id, &(claimable - amount));
+        }
+
+        wagers.insert(&quiz_id, &Wager { follow_up_quiz_id, amount: U128(amount), status: WagerStatus::Pending });
+        self.wagers.insert(&account_id, &wagers);
+        self.pending_wagers.insert(&account_id, &quiz_id);
+    }
+
+    pub fn cancel_wager(&mut self) -> U128 {
+        self.enter_phase(ANY_PHASE);
+        self.check_not_paused();
+        let account_id = env::predecessor_account_id();
+        let quiz_id = self.pending_wagers.get(&account_id).expect("You have no pending wager");
+        let mut wagers = self.wagers.get(&account_id).unwrap();
+        let mut wager = wagers.get(&quiz_id).unwrap();
+
+        let follow_up_open = self.quizzes.get(&wager.follow_up_quiz_id).is_some_and(|quiz| quiz.accepting_answers());
+        assert!(!follow_up_open, "The follow-up quiz is still open");
+
+        wager.status = WagerStatus::Refunded;
+        wagers.insert(&quiz_id, &wager);
+        self.wagers.insert(&account_id, &wagers);
+        self.pending_wagers.remove(&account_id);
+        self.transfer_prize(&account_id, wager.amount.0);
+
+        wager.amount
+    }
+
+    pub fn get_wager(&self, account_id: AccountId, quiz_id: QuizId) -> Option<Wager> {
+        self.wagers.get(&account_id).and_then(|wagers| wagers.get(&quiz_id))
+    }
+
+    pub fn pause(&mut self) {
+        self.enter_phase(ANY_PHASE);
+        self.check_owner();
+
+        if !self.paused {
+            self.paused = true;
+            emit_event("contract_paused", json!({ "block_height": env::block_height() }));
+        }
+    }
+
+    pub fn resume(&mut self) {
+        self.enter_phase(ANY_PHASE);
+        self.check_owner();
+
+        if self.paused {
+            self.paused = false;
+            emit_event("contract_resumed", json!({ "block_height": env::block_height() }));
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn initiate_shutdown(&mut self, grace_period_sec: u64) -> u64 {
+        self.enter_phase(ACTIVE_ONLY);
+        self.check_owner();
+
+        let closes_at = env::block_timestamp() + grace_period_sec * NANOS_PER_SEC;
+        self.phase = ContractPhase::ShuttingDown;
+        self.closes_at = Some(closes_at);
+
+        closes_at
+    }
+
+    pub fn set_activity_retention(&mut self, retention: u64) {
+        self.enter_phase(ANY_PHASE);
+        self.check_owner();
+        assert!(retention > 0, "Activity retention must be positive");
+
+        let entries = self.activity_entries();
+        let skip = entries.len().saturating_sub(retention as usize);
+        self.activity_log.clear();
+        for entry in entries.into_iter().skip(skip) {
+            self.activity_log.push(&entry);
+        }
+        self.activity_head = 0;
+        self.activity_retention = retention;
+    }
+
+    pub fn get_activity_since(&self, block_height: u64, limit: u32) -> ActivityDigest {
+        let mut digest = ActivityDigest {
+            published: vec![],
+            solved: vec![],
+            expired: vec![],
+            latest_block_height: env::block_height()
+        };
+
+        let mut taken = 0;
+        for entry in self.activity_entries().into_iter().filter(|entry| entry.block_height > block_height) {
+            // Never split a block across pages, so the checkpoint can't skip entries.
+            if taken >= limit && entry.block_height != digest.latest_block_height {
+                break;
+            }
+            taken += 1;
+            digest.latest_block_height = entry.block_height;
+
+            match entry.kind {
+                ActivityKind::Published => digest.published.push(entry.quiz_id),
+                ActivityKind::Solved { account_id, amount } => digest.solved.push(SolvedActivity {
+                    quiz_id: entry.quiz_id,
+                    account_id,
+                    amount: U128(amount)
+                }),
+                ActivityKind::Expired => digest.expired.push(entry.quiz_id)
+            }
+        }
+
+        if taken < limit {
+            digest.latest_block_height = env::block_height();
+        }
+
+        digest
+    }
+
+    pub fn get_shutdown_status(&self) -> ShutdownStatus {
+        ShutdownStatus { phase: self.current_phase(), closes_at: self.closes_at }
+    }
+
+    pub fn delete_quiz(&mut self, quiz_id: QuizId) -> u64 {
+        self.enter_phase(ANY_PHASE);
+        self.check_owner();
+
+        let quiz = self.quizzes.get(&quiz_id).unwrap_or_else(|| panic_json(QuizError::QuizNotFound { quiz_id }));
+        // Every way to get these back goes through the quiz, so deleting it first would strand them.
+        let nft_held = quiz.nft_prize.as_ref().is_some_and(|nft| nft.deposited_by.is_some() && !nft.delivered);
+        assert!(
+            quiz.escrow == 0 && !nft_held && quiz.deposits_held == 0,
+            "Refund the escrow, NFT prize, stakes and entry fees before deleting this quiz"
+        );
+        self.quizzes.remove(&quiz_id);
+        self.unlist_quiz(quiz_id, &quiz);
+        self.unindex_question(quiz_id, &quiz.question);
+
+        if let Some(mut reviewers) = self.draft_reviewers.remove(&quiz_id) {
+            reviewers.clear();
+        }
+
+        self.deleted_quiz_ids.insert(&quiz_id);
+
+        self.purge_participants(quiz_id, DELETE_CLEANUP_LIMIT)
+    }
+
+    pub fn cleanup_deleted_quiz(&mut self, quiz_id: QuizId, limit: u64) -> u64 {
+        self.enter_phase(ANY_PHASE);
+        self.check_owner();
+        assert!(self.deleted_quiz_ids.contains(&quiz_id), "Only deleted quizzes can be cleaned up");
+
+        self.purge_participants(quiz_id, limit)
+    }
+
+    // total counts every listed id; a page can come back shorter than limit when it covers expired or dangling ids.
+    pub fn get_published_quizzes(&self, from_index: u64, limit: u64) -> PublishedQuizzes {
+        let quiz_ids = self.published_quiz_ids.as_vector();
+        let mut quizzes = vec![];
+        let mut dangling_ids = vec![];
+        for index in from_index..quiz_ids.len().min(from_index.saturating_add(limit)) {
+            let quiz_id = quiz_ids.get(index).unwrap();
+            match self.quizzes.get(&quiz_id) {
+                Some(quiz) if quiz.is_live() => quizzes.push(quiz.to_json_quiz(quiz_id)),
+                Some(_) => {}
+                None => dangling_ids.push(quiz_id)
+            }
+        }
+        PublishedQuizzes { 
+            quizzes,
+            dangling_ids,
+            total: quiz_ids.len()
+        }
+    }
+
+    pub fn get_quizzes_by_category(&self, category: String, from_index: u64, limit: u64) -> Vec<JsonQuiz> {
+        let Some(quiz_ids) = self.category_quiz_ids.get(&category_key(&category)) else {
+            return vec![];
+        };
+
+        let quiz_ids = quiz_ids.as_vector();
+        (from_index..quiz_ids.len().min(from_index.saturating_add(limit)))
+            .filter_map(|index| {
+                let quiz_id = quiz_ids.get(index).unwrap();
+                self.quizzes.get(&quiz_id).filter(|quiz| quiz.is_live()).map(|quiz| quiz.to_json_quiz(quiz_id))
+            })
+            .collect()
+    }
+
+    pub fn create_series(&mut self, name: String) -> SeriesId {
+        self.enter_phase(ACTIVE_ONLY);
+        self.check_owner();
+        assert!(!name.trim().is_empty(), "Series name cannot be empty");
+
+        self.series.push(&name);
+        self.series.len() - 1
+    }
+
+    pub fn add_to_series(&mut self, series_id: SeriesId, quiz_id: QuizId) {
+        self.enter_phase(ACTIVE_ONLY);
+        self.check_owner();
+        assert!(series_id < self.series.len(), "No such series found");
+        self.expect_quiz(quiz_id);
+
+        let mut quiz_ids = self.series_quiz_ids.get(&series_id).unwrap_or_else(|| {
+            UnorderedSet::new(quiz_prefix(b's', series_id))
+        });
+        if quiz_ids.insert(&quiz_id) {
+            self.series_quiz_ids.insert(&series_id, &quiz_ids);
+        }
+    }
+
+    pub fn remove_from_series(&mut self, series_id: SeriesId, quiz_id: QuizId) {
+        self.enter_phase(ANY_PHASE);
+        self.check_owner();
+
+        let mut quiz_ids = self.series_quiz_ids.get(&series_id).expect("This series has no quizzes");
+        assert!(quiz_ids.remove(&quiz_id), "This quiz is not in the series");
+
+        if quiz_ids.is_empty() {
+            self.series_quiz_ids.remove(&series_id);
+        } else {
+            self.series_quiz_ids.insert(&series_id, &quiz_ids);
+        }
+    }
+
+    pub fn get_series_list(&self, from_index: u64, limit: u64) -> Vec<JsonSeries> {
+        (from_index..self.series.len().min(from_index.saturating_add(limit)))
+            .map(|series_id| JsonSeries {
+                series_id,
+                name: self.series.get(series_id).unwrap(),
+                quiz_count: self.series_quiz_ids.get(&series_id).map_or(0, |quiz_ids| quiz_ids.len())
+            })
+            .collect()
+    }
+
+    // Quiz ids are handed out in creation order, so sorting them keeps a series in the order it was authored.
+    pub fn get_series_quizzes(&self, series_id: SeriesId) -> Vec<JsonQuiz> {
+        let mut quiz_ids = self.series_quiz_ids.get(&series_id).map_or_else(Vec::new, |quiz_ids| quiz_ids.to_vec());
+        quiz_ids.sort_unstable();
+
+        quiz_ids
+            .into_iter()
+            .filter_map(|quiz_id| self.quizzes.get(&quiz_id).filter(|quiz| quiz.is_live()).map(|quiz| quiz.to_json_quiz(quiz_id)))
+            .collect()
+    }
+
+    pub fn repair_published_index(&mut self) -> Vec<QuizId> {
+        self.enter_phase(ANY_PHASE);
+        self.check_owner();
+
+        let dangling_ids: Vec<QuizId> = self.published_quiz_ids
+            .iter()
+            .filter(|quiz_id| !self.quizzes.contains_key(quiz_id))
+            .collect();
+
+        for quiz_id in &dangling_ids {
+            self.published_quiz_ids.remove(quiz_id);
+        }
+
+        for category in self.category_names.to_vec() {
+            let mut quiz_ids = self.category_quiz_ids.get(&category).unwrap();
+            let dangling_in_category: Vec<QuizId> = quiz_ids.iter().filter(|quiz_id| !self.quizzes.contains_key(quiz_id)).collect();
+            for quiz_id in &dangling_in_category {
+                quiz_ids.remove(quiz_id);
+            }
+            self.store_category(&category, quiz_ids);
+        }
+
+        dangling_ids
+    }
+
+    // Multipliers can lift a payout above the base prize, so a single quiz may be funded beyond its budget.
+    // A batch cannot tell which quiz the surplus was meant for and sends it back instead.
+    // The contract's own share (jackpot, forfeited wagers, spent insurance) goes back to the jackpot.
+    fn refund_share(&mut self, quiz_id: QuizId, quiz: &mut Quiz, account_id: &AccountId, contributed: u128) -> u128 {
+        let refund = match contributed.checked_mul(quiz.escrow) {
+            Some(scaled) => scaled / quiz.funded,
+            None => quiz.escrow / BASIS_POINTS * (contributed * BASIS_POINTS / quiz.funded)
+        };
+        quiz.escrow -= refund;
+        quiz.funded -= contributed;
+
+        match &quiz.prize_token {
+            _ if *account_id == env::current_account_id() => self.jackpot += refund,
+            Some(token) if refund > 0 => self.transfer_ft(token, account_id, refund),
+            None if refund > 0 => {
+                Promise::new(account_id.clone()).transfer(refund);
+            }
+            _ => {}
+        }
+
+        emit_event("quiz_refunded", json!({
+            "quiz_id": quiz_id,
+            "account_id": account_id,
+            "amount": U128(refund)
+        }));
+
+        refund
+    }
+
+    fn take_escrow(&self, required: u128, keep_surplus: bool) -> u128 {
+        let deposit = env::attached_deposit();
+        if deposit < required {
+            panic_json(QuizError::InsufficientDeposit { purpose: DepositPurpose::Escrow, required: U128(required), attached: U128(deposit) });
+        }
+        if keep_surplus {
+            return deposit;
+        }
+
+        if deposit > required {
+            Promise::new(env::predecessor_account_id()).transfer(deposit - required);
+        }
+        required
+    }
+
+    fn insert_quiz(&mut self, mut quiz: Quiz, allow_duplicate: bool) -> QuizId {
+        let quiz_id = self.current_quiz_id;
+        quiz.funded = quiz.escrow;
+        assert!(!self.deleted_quiz_ids.contains(&quiz_id), "Quiz id {} belongs to a deleted quiz and cannot be reused", quiz_id);
+        for prerequisite in &quiz.prerequisites {
+            assert!(self.quizzes.contains_key(prerequisite), "Prerequisite quiz {} does not exist", prerequisite);
+        }
+        self.index_question(quiz_id, &quiz.question, allow_duplicate);
+        let existing_quiz = self.quizzes.insert(&quiz_id, &quiz);
+
+        assert!(existing_quiz.is_none(), "Quiz with the same quiz_id already exists");
+        self.record_transition(quiz_id, QuizTransition::Created);
+
+        if quiz.status == QuizStatus::Published {
+            self.list_quiz(quiz_id, &quiz);
+            self.record_activity(quiz_id, ActivityKind::Published);
+            self.record_transition(quiz_id, QuizTransition::Published);
+        }
+
+        if quiz.escrow > 0 {
+            self.record_funding(quiz_id, &env::predecessor_account_id(), quiz.escrow);
+        }
+
+        self.current_quiz_id += 1;
+
+        quiz_id
+    }
+
+    fn add_funding(&mut self, quiz_id: QuizId, mut quiz: Quiz, account_id: &AccountId, amount: u128) -> u128 {
+        assert!(
+            matches!(quiz.status, QuizStatus::Published | QuizStatus::Unpublished),
+            "Only published or unpublished quizzes can be funded"
+        );
+
+        quiz.escrow += amount;
+        quiz.funded += amount;
+        self.quizzes.insert(&quiz_id, &quiz);
+        self.record_funding(quiz_id, account_id, amount);
+
+        emit_event("quiz_funded", json!({
+            "quiz_id": quiz_id,
+            "account_id": account_id,
+            "amount": U128(amount),
+            "token": quiz.prize_token,
+            "balance": U128(quiz.escrow)
+        }));
+
+        quiz.escrow
+    }
+
+    fn record_funding(&mut self, quiz_id: QuizId, account_id: &AccountId, amount: u128) {
+        let mut funders = self.quiz_funders.get(&quiz_id).unwrap_or_else(|| {
+            UnorderedMap::new(quiz_prefix(b'f', quiz_id))
+        });
+        let contributed = funders.get(account_id).unwrap_or(0) + amount;
+        funders.insert(account_id, &contributed);
+        self.quiz_funders.insert(&quiz_id, &funders);
+    }
+
+    fn list_quiz(&mut self, quiz_id: QuizId, quiz: &Quiz) {
+        self.published_quiz_ids.insert(&quiz_id);
+
+        if let Some(category) = &quiz.category {
+            let mut quiz_ids = self.category_quiz_ids.get(category).unwrap_or_else(|| UnorderedSet::new(category_prefix(category)));
+            quiz_ids.insert(&quiz_id);
+            self.store_category(category, quiz_ids);
+        }
+    }
+
+    fn unlist_quiz(&mut self, quiz_id: QuizId, quiz: &Quiz) {
+        self.published_quiz_ids.remove(&quiz_id);
+
+        if let Some(mut quiz_ids) = quiz.category.as_ref().and_then(|category| self.category_quiz_ids.get(category)) {
+            quiz_ids.remove(&quiz_id);
+            self.store_category(quiz.category.as_ref().unwrap(), quiz_ids);
+        }
+    }
+
+    fn store_category(&mut self, category: &String, quiz_ids: UnorderedSet<QuizId>) {
+        if quiz_ids.is_empty() {
+            self.category_quiz_ids.remove(category);
+            self.category_names.remove(category);
+        } else {
+            self.category_quiz_ids.insert(category, &quiz_ids);
+            self.category_names.insert(category);
+        }
+    }
+
+    fn duplicate_of(&self, key: &String) -> Option<QuizId> {
+        self.question_index
+            .get(key)
+            .filter(|existing_quiz_id| self.quizzes.get(existing_quiz_id).is_some_and(|quiz| quiz.status != QuizStatus::Archived))
+    }
+
+    fn index_question(&mut self, quiz_id: QuizId, question: &str, allow_duplicate: bool) {
+        let key = question_key(question);
+        if let Some(existing_quiz_id) = self.duplicate_of(&key) {
+            assert!(allow_duplicate, "duplicate question (see quiz {})", existing_quiz_id);
+            return;
+        }
+
+        self.question_index.insert(&key, &quiz_id);
+    }
+
+    fn unindex_question(&mut self, quiz_id: QuizId, question: &str) {
+        let key = question_key(question);
+        if self.question_index.get(&key) == Some(quiz_id) {
+            self.question_index.remove(&key);
+        }
+    }
+
+    fn record_transition(&mut self, quiz_id: QuizId, transition: QuizTransition) {
+        let mut history = self.quiz_history.get(&quiz_id).unwrap_or_else(|| {
+            Vector::new(quiz_prefix(b'h', quiz_id))
+        });
+        history.push(&StatusChange { transition, timestamp: env::block_timestamp(), account_id: env::predecessor_account_id() });
+        self.quiz_history.insert(&quiz_id, &history);
+    }
+
+    fn record_activity(&mut self, quiz_id: QuizId, kind: ActivityKind) {
+        let entry = ActivityEntry { block_height: env::block_height(), quiz_id, kind };
+        if self.activity_log.len() < self.activity_retention {
+            self.activity_log.push(&entry);
+            return;
+        }
+
+        self.activity_log.replace(self.activity_head, &entry);
+        self.activity_head = (self.activity_head + 1) % self.activity_log.len();
+    }
+
+    fn activity_entries(&self) -> Vec<ActivityEntry> {
+        let len = self.activity_log.len();
+        (0..len).filter_map(|offset| self.activity_log.get((self.activity_head + offset) % len)).collect()
+    }
+
+    fn quiz_drafts(&self, from_index: QuizId, limit: u64, status: Option<QuizStatus>) -> Vec<JsonQuizDraft> {
+        (from_index..self.current_quiz_id.min(from_index.saturating_add(limit)))
+            .filter_map(|quiz_id| self.quizzes.get(&quiz_id).filter(|quiz| status.is_none_or(|status| quiz.status == status)).map(|quiz| quiz.to_json_draft(quiz_id)))
+            .collect()
+    }
+
+    fn current_phase(&self) -> ContractPhase {
+        match (self.phase, self.closes_at) {
+            (ContractPhase::ShuttingDown, Some(closes_at)) if env::block_timestamp() >= closes_at => ContractPhase::Closed,
+            (phase, _) => phase
+        }
+    }
+
+    fn enter_phase(&mut self, allowed: &[ContractPhase]) {
+        self.phase = self.current_phase();
+        if !allowed.contains(&self.phase) {
+            panic_json(QuizError::PhaseNotAllowed { phase: self.phase });
+        }
+    }
+
+    fn check_not_paused(&self) {
+        if self.paused {
+            panic_json(QuizError::ContractPaused);
+        }
+    }
+
+    fn expect_quiz(&self, quiz_id: QuizId) -> Quiz {
+        self.quizzes.get(&quiz_id).unwrap_or_else(|| {
+            if self.deleted_quiz_ids.contains(&quiz_id) {
+                panic_json(QuizError::QuizDeleted { quiz_id });
+            }
+            panic_json(QuizError::QuizNotFound { quiz_id })
+        })
+    }
+
+    fn pay_prize(&mut self, quiz_id: QuizId, quiz: &mut Quiz, account_id: &AccountId, recipient: &AccountId, solve: SolvedAttempt) -> u128 {
+        let SolvedAttempt { attempt_number, placement, credit_bps } = solve;
+        let context = PayoutContext {
+            revealed_stage: quiz.revealed_parts(),
+            unsolved_sec: quiz.unsolved_sec(),
+            treasury_fee_bps: self.treasury.as_ref().map_or(0, |treasury| treasury.fee_bps),
+            difficulty_bps: self.difficulty_multipliers.bps(quiz.difficulty),
+            streak_bps: self.streak_config.bps(self.get_streak(account_id.clone()).current),
+            credit_bps,
+            hint_penalty_bps: if self.retry_record(account_id, quiz_id, quiz).hint_used { quiz.hint_penalty_bps } else { 0 },
+            time_decay_bps: quiz.time_decay_bps(),
+            account_cap: self.remaining_winnings(account_id, quiz),
+            pool: Some(quiz.escrow)
+        };
+        let breakdown = compute_payout(&quiz.payout_params(placement), attempt_number, &context);
+        self.record_payout(quiz_id, quiz, account_id, recipient, &solve, breakdown)
+    }
+
+    fn record_payout(&mut self, quiz_id: QuizId, quiz: &mut Quiz, account_id: &AccountId, recipient: &AccountId, solve: &SolvedAttempt, breakdown: PayoutBreakdown) -> u128 {
+        let SolvedAttempt { attempt_number, placement, .. } = *solve;
+        quiz.escrow -= breakdown.final_amount;
+        quiz.paid_out += breakdown.final_amount;
+        if quiz.prize_token.is_none() {
+            self.add_winnings(account_id, breakdown.final_amount);
+            self.near_distributed += breakdown.final_amount;
+        }
+
+        let record_index = self.payout_records.len();
+        self.payout_records.push(&PayoutRecord {
+            quiz_id,
+            account_id: account_id.clone(),
+            recipient: recipient.clone(),
+            attempt_number,
+            base_prize: U128(breakdown.base_prize),
+            modifiers_applied: breakdown.modifiers,
+            final_amount: U128(breakdown.final_amount),
+            treasury_fee: U128(breakdown.treasury_fee),
+            token: quiz.prize_token.clone(),
+            block_height: env::block_height(),
+            round: quiz.round
+        });
+
+        let mut account_payouts = self.account_payouts.get(account_id).unwrap_or_else(|| {
+            Vector::new(account_prefix(b'p', account_id))
+        });
+        account_payouts.push(&record_index);
+        self.account_payouts.insert(account_id, &account_payouts);
+
+        let prize = breakdown.solver_amount;
+        let treasury_fee = breakdown.treasury_fee;
+        match quiz.vesting.as_ref().filter(|vesting| prize >= vesting.threshold.0) {
+            Some(vesting) => self.grant_vesting(quiz_id, recipient, prize, vesting),
+            None => self.send_prize(quiz.prize_token.as_ref(), recipient, prize)
+        }
+        match &quiz.prize_token {
+            Some(token) if treasury_fee > 0 => {
+                let accrued = self.accrued_token_fees.get(token).unwrap_or(0) + treasury_fee;
+                self.accrued_token_fees.insert(token, &accrued);
+            }
+            _ => self.accrued_fees += treasury_fee
+        }
+        if quiz.prize_token.is_none() {
+            self.reward_referrer(quiz_id, account_id, breakdown.final_amount);
+        }
+
+        self.record_activity(quiz_id, ActivityKind::Solved { account_id: account_id.clone(), amount: prize });
+
+        emit_event("quiz_solved", json!({
+            "quiz_id": quiz_id,
+            "account_id": account_id,
+            "recipient": recipient,
+            "attempt_number": attempt_number,
+            "placement": placement,
+            "amount": U128(prize),
+            "treasury_fee": U128(treasury_fee)
+        }));
+
+        prize
+    }
+
+    fn staked(&self, quiz_id: QuizId, account_id: &AccountId) -> u128 {
+        self.stakes.get(&quiz_id).and_then(|stakes| stakes.get(account_id)).unwrap_or(0)
+    }
+
+    fn take_entry_fees(&mut self, quiz_id: QuizId, quiz: &mut Quiz, account_id: &AccountId) -> Option<u128> {
+        let mut fees = self.entry_fees.get(&quiz_id)?;
+        let amount = fees.remove(account_id)?;
+        self.entry_fees.insert(&quiz_id, &fees);
+        quiz.deposits_held -= amount;
+        Some(amount)
+    }
+
+    fn take_stake(&mut self, quiz_id: QuizId, quiz: &mut Quiz, account_id: &AccountId) -> Option<u128> {
+        let mut stakes = self.stakes.get(&quiz_id)?;
+        let amount = stakes.remove(account_id)?;
+        self.stakes.insert(&quiz_id, &stakes);
+        quiz.deposits_held -= amount;
+        Some(amount)
+    }
+
+    fn grant_vesting(&mut self, quiz_id: QuizId, recipient: &AccountId, amount: u128, vesting: &VestingPolicy) {
+        let mut grants = self.vesting.get(recipient).unwrap_or_else(|| {
+            Vector::new(account_prefix(b'e', recipient))
+        });
+        grants.push(&VestingGrant {
+            quiz_id,
+            total: amount,
+            claimed: 0,
+            starts_at: env::block_timestamp(),
+            tranches: vesting.tranches,
+            interval_sec: vesting.interval_sec
+        });
+        self.vesting.insert(recipient, &grants);
+
+        emit_event("prize_vesting", json!({
+            "quiz_id": quiz_id,
+            "account_id": recipient,
+            "amount": U128(amount),
+            "tranches": vesting.tranches,
+            "interval_sec": vesting.interval_sec
+        }));
+    }
+
+    fn account_winnings(&self, account_id: &AccountId) -> AccountWinnings {
+        let mut winnings = self.winnings.get(account_id).unwrap_or_default();
+        if winnings.season != self.season {
+            winnings.season = self.season;
+            winnings.season_total = 0;
+        }
+        winnings
+    }
+
+    // Token prizes don't count towards the cap, it is denominated in NEAR.
+    fn remaining_winnings(&self, account_id: &AccountId, quiz: &Quiz) -> Option<u128> {
+        let cap = self.payout_cap.as_ref().filter(|_| quiz.prize_token.is_none())?;
+        let winnings = self.account_winnings(account_id);
+        let won = if cap.per_season { winnings.season_total } else { winnings.total };
+        Some(cap.amount.0.saturating_sub(won))
+    }
+
+    fn add_winnings(&mut self, account_id: &AccountId, amount: u128) {
+        let mut winnings = self.account_winnings(account_id);
+        winnings.total += amount;
+        winnings.season_total += amount;
+        self.winnings.insert(account_id, &winnings);
+        self.rank_account(account_id);
+    }
+
+    // Only the top LEADERBOARD_SIZE accounts are kept, re-ranking whichever account's tally just changed.
+    fn rank_account(&mut self, account_id: &AccountId) {
+        let entry = LeaderboardEntry {
+            account_id: account_id.clone(),
+            solved: self.account_tallies.get(account_id).map_or(0, |tally| tally.solved),
+            winnings: U128(self.account_winnings(account_id).total)
+        };
+        self.leaderboard.retain(|ranked| &ranked.account_id != account_id);
+        let rank = self.leaderboard.partition_point(|ranked| (ranked.solved, ranked.winnings.0) >= (entry.solved, entry.winnings.0));
+        if rank < LEADERBOARD_SIZE {
+            self.leaderboard.insert(rank, entry);
+            self.leaderboard.truncate(LEADERBOARD_SIZE);
+        }
+    }
+
+    fn record_split_share(&mut self, quiz_id: QuizId, share: SplitShare) {
+        let mut winners = self.split_winners.get(&quiz_id).unwrap_or_else(|| {
+            Vector::new(quiz_prefix(b'x', quiz_id))
+        });
+        winners.push(&share);
+        self.split_winners.insert(&quiz_id, &winners);
+    }
+
+    fn send_prize(&mut self, token: Option<&AccountId>, recipient: &AccountId, prize: u128) {
+        match token {
+            Some(token) if self.pull_payouts => self.credit_tokens(recipient, token, prize),
+            Some(token) => self.transfer_ft(token, recipient, prize),
+            None => self.transfer_prize(recipient, prize)
+        }
+    }
+
+    fn credit_tokens(&mut self, account_id: &AccountId, token_id: &AccountId, amount: u128) {
+        let mut claimable = self.claimable_tokens.get(account_id).unwrap_or_else(|| {
+            LookupMap::new(account_prefix(b'k', account_id))
+        });
+        let accrued = claimable.get(token_id).unwrap_or(0) + amount;
+        claimable.insert(token_id, &accrued);
+        self.claimable_tokens.insert(account_id, &claimable);
+    }
+
+    // Failed token transfers are kept claimable instead of being lost, see on_ft_transfer.
+    fn transfer_ft(&self, token_id: &AccountId, receiver_id: &AccountId, amount: u128) {
+        let args = json!({ "receiver_id": receiver_id, "amount": U128(amount) }).to_string().into_bytes();
+        Promise::new(token_id.clone())
+            .function_call("ft_transfer".to_owned(), args, 1, GAS_FOR_FT_TRANSFER)
+            .then(Self::ext(env::current_account_id()).with_static_gas(GAS_FOR_FT_CALLBACK).on_ft_transfer(token_id.clone(), receiver_id.clone(), U128(amount)));
+    }
+
+    fn transfer_nft(&self, quiz_id: QuizId, token: &NftToken, receiver_id: &AccountId) {
+        let args = json!({ "receiver_id": receiver_id, "token_id": token.token_id }).to_string().into_bytes();
+        Promise::new(token.contract_id.clone())
+            .function_call("nft_transfer".to_owned(), args, 1, GAS_FOR_NFT_TRANSFER)
+            .then(Self::ext(env::current_account_id()).with_static_gas(GAS_FOR_NFT_CALLBACK).on_nft_transfer(quiz_id));
+    }
+
+    fn transfer_prize(&mut self, recipient: &AccountId, prize: u128) {
+        let accrued = self.claimable_prizes.get(recipient).unwrap_or(0) + prize;
+        if self.pull_payouts || accrued < self.payout_preferences.get(recipient).unwrap_or(0) {
+            self.claimable_prizes.insert(recipient, &accrued);
+            return;
+        }
+
+        self.claimable_prizes.remove(recipient);
+        self.transfer_near(recipient, accrued);
+    }
+
+    // A failed transfer is credited back to claimable_prizes instead of being lost.
+    fn transfer_near(&self, receiver_id: &AccountId, amount: u128) {
+        Promise::new(receiver_id.clone())
+            .transfer(amount)
+            .then(Self::ext(env::current_account_id()).with_static_gas(GAS_FOR_PRIZE_CALLBACK).on_prize_transfer(receiver_id.clone(), U128(amount)));
+    }
+
+    fn track_participant(&mut self, quiz_id: QuizId, account_id: &AccountId) {
+        let mut participants = self.quiz_participants.get(&quiz_id).unwrap_or_else(|| {
+            UnorderedSet::new(quiz_prefix(b'q', quiz_id))
+        });
+        if participants.insert(account_id) {
+            self.quiz_participants.insert(&quiz_id, &participants);
+        }
+    }
+
+    fn purge_participants(&mut self, quiz_id: QuizId, limit: u64) -> u64 {
+        let Some(mut participants) = self.quiz_participants.get(&quiz_id) else {
+            return 0;
+        };
+
+        let batch: Vec<AccountId> = participants.iter().take(limit as usize).collect();
+        for account_id in batch {
+            if let Some(mut records) = self.retries_left.get(&account_id) {
+                records.remove(&quiz_id);
+            }
+            if let Some(mut solved) = self.solved_quizzes.get(&account_id) {
+                if solved.remove(&quiz_id) {
+                    self.solved_quizzes.insert(&account_id, &solved);
+                }
+            }
+            participants.remove(&account_id);
+        }
+
+        let remaining = participants.len();
+        if remaining == 0 {
+            self.quiz_participants.remove(&quiz_id);
+        } else {
+            self.quiz_participants.insert(&quiz_id, &participants);
+        }
+
+        remaining
+    }
+
+    // The doubling comes out of the follow-up's escrow, and a forfeited prize stays in it as the contract's own funding.
+    fn settle_wager(&mut self, account_id: &AccountId, follow_up_quiz_id: QuizId, follow_up: &mut Quiz, solved_on_attempt: Option<u8>, prize_pool: u128) {
+        let Some(quiz_id) = self.pending_wagers.get(account_id) else {
+            return;
+        };
+        let mut wagers = self.wagers.get(account_id).unwrap();
+        let mut wager = wagers.get(&quiz_id).unwrap();
+        if wager.follow_up_quiz_id != follow_up_quiz_id {
+            return;
+        }
+
+        let amount = wager.amount.0;
+        match solved_on_attempt {
+            Some(1) => {
+                wager.status = WagerStatus::Won;
+                let bonus = amount.min(prize_pool).min(follow_up.escrow);
+                follow_up.escrow -= bonus;
+                follow_up.paid_out += bonus;
+                self.near_distributed += bonus;
+                self.transfer_prize(account_id, amount + bonus);
+            }
+            Some(_) => {
+                wager.status = WagerStatus::Refunded;
+                self.transfer_prize(account_id, amount);
+            }
+            None => {
+                wager.status = WagerStatus::Forfeited;
+                follow_up.escrow += amount;
+                follow_up.funded += amount;
+                self.record_funding(follow_up_quiz_id, &env::current_account_id(), amount);
+            }
+        }
+
+        wagers.insert(&quiz_id, &wager);
+        self.wagers.insert(account_id, &wagers);
+        self.pending_wagers.remove(account_id);
+    }
+
+    // Pooled fees are booked as the owner's funding, so they come back through refund_quiz if nobody wins.
+    fn collect_attempt_fee(&mut self, quiz_id: QuizId, quiz: &mut Quiz, account_id: &AccountId, fee: u128) {
+        if quiz.entry_refund_rake_bps.is_some() {
+            if fee > 0 {
+                let mut fees = self.entry_fees.get(&quiz_id).unwrap_or_else(|| {
+                    LookupMap::new(quiz_prefix(b'b', quiz_id))
+                });
+                fees.insert(account_id, &(fees.get(account_id).unwrap_or(0) + fee));
+                self.entry_fees.insert(&quiz_id, &fees);
+                quiz.deposits_held += fee;
+            }
+            return;
+        }
+        if !quiz.pool_entry_fees {
+            self.withdrawable_balance += fee;
+            return;
+        }
+
+        quiz.escrow += fee;
+        quiz.funded += fee;
+        self.record_funding(quiz_id, &self.owner_id.clone(), fee);
+    }
+
+    fn attempt_fee(&self, account_id: &AccountId, quiz: &Quiz, retries_left: usize) -> u128 {
+        if self.has_season_pass(account_id.clone()).is_some() {
+            return 0;
+        }
+
+        quiz.fee_schedule.get(quiz.max_retries - retries_left).copied().unwrap_or(0)
+    }
+
+    // Counters every correct solve updates, whether or not it wins anything. Runs after the payout so the streak bonus uses the streak before this solve.
+    fn record_solve(&mut self, account_id: &AccountId) {
+        self.extend_streak(account_id);
+        let mut tally = self.account_tallies.get(account_id).unwrap_or_default();
+        tally.solved += 1;
+        self.account_tallies.insert(account_id, &tally);
+        self.total_solves += 1;
+        if tally.solved == 1 {
+            self.unique_solvers += 1;
+        }
+        self.rank_account(account_id);
+    }
+
+    // Marks the quiz solved for the account and pays out the share of parts that were right.
+    fn reward_solver(&mut self, account_id: &AccountId, quiz_id: QuizId, mut quiz: Quiz, record: RetryRecord, payout_to: Option<AccountId>, credit: (usize, usize)) -> SubmitResult {
+        let (parts_correct, parts) = credit;
+        // Wagers are in NEAR and cannot be matched out of a token pool.
+        let prize_pool = if quiz.prize_token.is_some() { 0 } else { quiz.effective_prize() };
+        let retries_left = record.retries_left;
+        let insurance = record.insurance;
+
+        let mut solved_quizzes_set = self.solved_quizzes.get(account_id).unwrap_or_else(|| {
+            UnorderedSet::new(account_prefix(b's', account_id))
+        });
+        solved_quizzes_set.insert(&quiz_id);
+        self.solved_quizzes.insert(account_id, &solved_quizzes_set);
+
+        let mut retries_left_map = self.retries_left.get(account_id).unwrap_or_else(|| {
+            LookupMap::new(account_prefix(b'u', account_id))
+        });
+        retries_left_map.insert(&quiz_id, &RetryRecord { insurance: None, ..record });
+        self.retries_left.insert(account_id, &retries_left_map);
+        self.track_participant(quiz_id, account_id);
+
+        if let Some(insurance) = insurance {
+            Promise::new(account_id.clone()).transfer(insurance);
+        }
+
+        let placement = quiz.next_placement();
+        let prize_pool_exhausted = quiz.winners_exhausted() || quiz.escrow == 0;
+        let first_solver = quiz.first_solver.is_none();
+        quiz.solver_count += 1;
+        if !prize_pool_exhausted {
+            quiz.winner_count += 1;
+            if let Some(fees) = self.take_entry_fees(quiz_id, &mut quiz, account_id) {
+                self.withdrawable_balance += fees;
+            }
+        }
+        quiz.first_solved_at.get_or_insert_with(env::block_timestamp);
+        quiz.first_solver.get_or_insert_with(|| account_id.clone());
+
+        let recipient = payout_to.unwrap_or_else(|| account_id.clone());
+        let nft_won = match quiz.nft_prize.as_mut().filter(|nft| nft.deposited_by.is_some() && nft.winner.is_none()) {
+            Some(nft) => {
+                nft.winner = Some(recipient.clone());
+                self.transfer_nft(quiz_id, &nft.token, &recipient);
+                true
+            }
+            None => false
+        };
+
+        let attempt_number = (quiz.max_retries + 1 - retries_left) as u8;
+        if prize_pool_exhausted {
+            self.record_solve(account_id);
+            self.settle_wager(account_id, quiz_id, &mut quiz, Some(attempt_number), 0);
+            self.quizzes.insert(&quiz_id, &quiz);
+
+            let message = if nft_won { "Your answer is correct. You won the NFT prize" } else { "Your answer is correct, but the prize pool is exhausted" };
+            return SubmitResult {
+                correct: true,
+                message: message.to_owned(),
+                amount: None,
+                retries_left,
+                feedback: None,
+                prize_pool_exhausted
+            };
+        }
+
+        let mut amount = if quiz.prize_split.is_some() {
+            self.record_split_share(quiz_id, SplitShare { account_id: account_id.clone(), recipient: recipient.clone(), attempt_number });
+            0
+        } else {
+            let credit_bps = (parts_correct as u128 * BASIS_POINTS / parts as u128) as u32;
+            let solve = SolvedAttempt { attempt_number, placement, credit_bps };
+            self.pay_prize(quiz_id, &mut quiz, account_id, &recipient, solve)
+        };
+        self.record_solve(account_id);
+        if let Some(bonus) = quiz.first_solver_bonus.filter(|_| first_solver).map(|bonus| bonus.min(quiz.escrow)).filter(|&bonus| bonus > 0) {
+            quiz.escrow -= bonus;
+            quiz.paid_out += bonus;
+            if quiz.prize_token.is_none() {
+                self.near_distributed += bonus;
+            }
+            self.send_prize(quiz.prize_token.as_ref(), &recipient, bonus);
+            amount += bonus;
+
+            emit_event("first_solver_bonus", json!({
+                "quiz_id": quiz_id,
+                "account_id": account_id,
+                "recipient": recipient,
+                "amount": U128(bonus)
+            }));
+        }
+        self.settle_wager(account_id, quiz_id, &mut quiz, Some(attempt_number), prize_pool);
+        self.quizzes.insert(&quiz_id, &quiz);
+
+        let message = if quiz.prize_split.is_some() {
+            "Your answer is correct. Your share of the pool is paid out when the quiz closes".to_owned()
+        } else if parts_correct < parts {
+            format!("{} of {} parts are correct. You've got {} yoctoNEAR", parts_correct, parts, amount)
+        } else {
+            format!("Your answer is correct. You've got {} yoctoNEAR", amount)
+        };
+
+        SubmitResult {
+            correct: true,
+            message,
+            amount: Some(U128(amount)),
+            retries_left,
+            feedback: None,
+            prize_pool_exhausted
+        }
+    }
+
+    // Retry insurance absorbs the miss when the account holds it. Returns the retries left afterwards.
+    fn record_miss(&mut self, account_id: &AccountId, quiz_id: QuizId, quiz: &mut Quiz, record: RetryRecord) -> usize {
+        let mut retries_left = record.retries_left;
+        // Spent insurance grows a NEAR prize pool; a token pool cannot hold it, so it goes to the owner balance there.
+        match record.insurance {
+            Some(insurance) if quiz.prize_token.is_none() => {
+                quiz.escrow += insurance;
+                quiz.funded += insurance;
+                self.record_funding(quiz_id, &env::current_account_id(), insurance);
+            }
+            Some(insurance) => self.withdrawable_balance += insurance,
+            None => retries_left -= 1
+        }
+
+        let mut retries_left_map = self.retries_left.get(account_id).unwrap_or_else(|| {
+            LookupMap::new(account_prefix(b'u', account_id))
+        });
+        retries_left_map.insert(&quiz_id, &RetryRecord { retries_left, insurance: None, last_failed_at: Some(env::block_timestamp()), ..record });
+        self.retries_left.insert(account_id, &retries_left_map);
+        self.track_participant(quiz_id, account_id);
+        self.break_streak(account_id);
+        if retries_left == 0 {
+            let prize_pool = quiz.effective_prize();
+            self.settle_wager(account_id, quiz_id, quiz, None, prize_pool);
+        }
+        self.quizzes.insert(&quiz_id, quiz);
+
+        retries_left
+    }
+
+    fn reward_referrer(&mut self, quiz_id: QuizId, account_id: &AccountId, prize: u128) {
+        let Some(mut referral) = self.referrals.get(account_id).filter(|referral| referral.rewarded_wins < self.referral_config.rewarded_wins) else {
+            return;
+        };
+        referral.rewarded_wins += 1;
+        self.referrals.insert(account_id, &referral);
+
+        let bonus = apply_bps(prize, self.referral_config.bonus_bps as u128).min(self.accrued_fees);
+        if bonus == 0 {
+            return;
+        }
+        self.accrued_fees -= bonus;
+        let mut stats = self.get_referrer_stats(referral.referrer.clone());
+        stats.earned = U128(stats.earned.0 + bonus);
+        self.referrers.insert(&referral.referrer, &stats);
+        self.send_prize(None, &referral.referrer, bonus);
+
+        emit_event("referral_rewarded", json!({
+            "quiz_id": quiz_id,
+            "account_id": account_id,
+            "referrer": referral.referrer,
+            "amount": U128(bonus)
+        }));
+    }
+
+    // The multiplier for a solve uses the streak before it, so the first solve of a run is paid as usual.
+    fn extend_streak(&mut self, account_id: &AccountId) {
+        let mut streak = self.get_streak(account_id.clone());
+        streak.current += 1;
+        streak.best = streak.best.max(streak.current);
+        self.streaks.insert(account_id, &streak);
+    }
+
+    fn break_streak(&mut self, account_id: &AccountId) {
+        if let Some(streak) = self.streaks.get(account_id).filter(|streak| streak.current > 0) {
+            self.streaks.insert(account_id, &Streak { current: 0, ..streak });
+        }
+    }
+
+    // Everything that rules out an attempt before any fee is taken, in the order submit_answer reports it.
+    fn submission_error(&self, account_id: &AccountId, quiz_id: QuizId, quiz: &Quiz, revealed: bool) -> Option<QuizError> {
+        if self.storage_required && !self.storage_deposits.contains_key(account_id) {
+            return Some(QuizError::StorageNotRegistered { account_id: account_id.clone() });
+        }
+        if !quiz.is_live() {
+            return Some(QuizError::QuizNotPublished { quiz_id });
+        }
+        if quiz.is_stale() {
+            return Some(QuizError::StaleEpoch { quiz_id, epoch_height: quiz.epoch_height });
+        }
+        if let Some(expires_at) = quiz.expires_at.filter(|_| quiz.is_expired()) {
+            return Some(QuizError::QuizExpired { quiz_id, expires_at });
+        }
+        if quiz.commit_reveal && !revealed {
+            return Some(QuizError::CommitRequired { quiz_id });
+        }
+        let missing = self.missing_prerequisites(account_id, quiz);
+        if !missing.is_empty() {
+            return Some(QuizError::PrerequisitesNotMet { quiz_id, missing });
+        }
+        if let Some(required) = quiz.stake_required.filter(|&required| self.staked(quiz_id, account_id) < required) {
+            return Some(QuizError::StakeRequired { quiz_id, required: U128(required) });
+        }
+
+        let (solved, retries_left) = self.participation(account_id, quiz_id, quiz);
+        if solved {
+            return Some(QuizError::AlreadySolved { quiz_id });
+        }
+        if retries_left == 0 {
+            return Some(QuizError::OutOfRetries { quiz_id, refresh_at: None });
+        }
+        if self.pending_submission(account_id, quiz_id, quiz).is_some() {
+            return Some(QuizError::ReviewPending { quiz_id });
+        }
+        let record = self.retry_record(account_id, quiz_id, quiz);
+        if let Some(retry_at) = record.last_failed_at.map(|failed_at| failed_at + quiz.cooldown_sec * NANOS_PER_SEC) {
+            if env::block_timestamp() < retry_at {
+                return Some(QuizError::AttemptCooldown { quiz_id, retry_at });
+            }
+        }
+
+        if quiz.placements_exhausted() {
+            return Some(QuizError::PlacementsExhausted { quiz_id });
+        }
+        if quiz.global_attempts_remaining() == Some(0) {
+            return Some(QuizError::GuessBudgetExhausted { quiz_id });
+        }
+
+        None
+    }
+
+    fn batch_error(&self, account_id: &AccountId, quiz_id: QuizId, quiz: &Quiz) -> Option<QuizError> {
+        if quiz.answer_count() != 1 {
+            return Some(QuizError::WrongAnswerCount { quiz_id, expected: quiz.answer_count() });
+        }
+        let (_, retries_left) = self.participation(account_id, quiz_id, quiz);
+        let fee = self.attempt_fee(account_id, quiz, retries_left);
+        if fee > 0 {
+            return Some(QuizError::InsufficientDeposit { purpose: DepositPurpose::Attempt, required: U128(fee), attached: U128(0) });
+        }
+
+        let started = self.attempt_starts.get(account_id)
+            .and_then(|starts| starts.get(&quiz_id))
+            .is_some_and(|start| start.round == quiz.round);
+        (quiz.attempt_window_sec.is_some() && !started).then_some(QuizError::AttemptNotStarted { quiz_id })
+    }
+
+    fn queue_submission(&mut self, account_id: &AccountId, quiz_id: QuizId, quiz: &Quiz, answer: String, payout_to: Option<AccountId>) {
+        let mut submissions = self.submissions.get(account_id).unwrap_or_else(|| {
+            LookupMap::new(account_prefix(b'v', account_id))
+        });
+        let submission = Submission { round: quiz.round, answer, submitted_at: env::block_timestamp(), payout_to, status: ReviewStatus::Pending };
+        submissions.insert(&quiz_id, &submission);
+        self.submissions.insert(account_id, &submissions);
+
+        let mut queue = self.review_queues.get(&quiz_id).unwrap_or_else(|| {
+            UnorderedSet::new(quiz_prefix(b'g', quiz_id))
+        });
+        queue.insert(account_id);
+        self.review_queues.insert(&quiz_id, &queue);
+        self.track_participant(quiz_id, account_id);
+    }
+
+    fn pending_submission(&self, account_id: &AccountId, quiz_id: QuizId, quiz: &Quiz) -> Option<Submission> {
+        self.submissions
+            .get(account_id)
+            .and_then(|submissions| submissions.get(&quiz_id))
+            .filter(|submission| submission.round == quiz.round && submission.status == ReviewStatus::Pending)
+    }
+
+    fn record_attempt(&mut self, account_id: &AccountId, quiz_id: QuizId, round: u32, correct: bool) {
+        let mut history = self.attempt_history.get(account_id).unwrap_or_else(|| {
+            LookupMap::new(account_prefix(b'y', account_id))
+        });
+        let mut attempts = history.get(&quiz_id).unwrap_or_else(|| {
+            Vector::new(account_quiz_prefix(b'z', account_id, quiz_id))
+        });
+
+        attempts.push(&AttemptRecord { round, correct, timestamp: env::block_timestamp() });
+        history.insert(&quiz_id, &attempts);
+        self.attempt_history.insert(account_id, &history);
+    }
+
+    fn take_attempt_start(&mut self, account_id: &AccountId, quiz_id: QuizId, quiz: &Quiz) -> Option<u64> {
+        let mut starts = self.attempt_starts.get(account_id)?;
+        let start = starts.remove(&quiz_id).filter(|start| start.round == quiz.round);
+        self.attempt_starts.insert(account_id, &starts);
+
+        start.map(|start| start.started_at)
+    }
+
+    // Records from an earlier round read as a fresh start on the current one.
+    fn retry_record(&self, account_id: &AccountId, quiz_id: QuizId, quiz: &Quiz) -> RetryRecord {
+        self.stored_retry_record(account_id, quiz_id)
+            .filter(|record| record.round == quiz.round)
+            .unwrap_or(RetryRecord { round: quiz.round, retries_left: quiz.max_retries, insurance: None, hint_used: false, last_failed_at: None, step: 0 })
+    }
+
+    // A prerequisite solved in any earlier round still counts.
+    fn missing_prerequisites(&self, account_id: &AccountId, quiz: &Quiz) -> Vec<QuizId> {
+        let solved = self.solved_quizzes.get(account_id);
+        quiz.prerequisites
+            .iter()
+            .copied()
+            .filter(|prerequisite| !solved.as_ref().is_some_and(|solved| solved.contains(prerequisite)))
+            .collect()
+    }
+
+    // Counts left by the baseline layout stand in for records until the account plays again.
+    fn stored_retry_record(&self, account_id: &AccountId, quiz_id: QuizId) -> Option<RetryRecord> {
+        self.retries_left.get(account_id).and_then(|records| records.get(&quiz_id)).or_else(|| {
+            migrate::baseline_retries_left(account_id, quiz_id)
+                .map(|retries_left| RetryRecord { round: 0, retries_left, insurance: None, hint_used: false, last_failed_at: None, step: 0 })
+        })
+    }
+
+    fn participation(&self, account_id: &AccountId, quiz_id: QuizId, quiz: &Quiz) -> (bool, usize) {
+        let record = self.stored_retry_record(account_id, quiz_id);
+        let current_round = record.as_ref().map_or(0, |record| record.round) == quiz.round;
+        let solved = current_round && self.solved_quizzes.get(account_id).is_some_and(|solved| solved.contains(&quiz_id));
+        let retries_left = record.filter(|_| current_round).map_or(quiz.max_retries, |record| record.retries_left);
+
+        (solved, retries_left)
+    }
+
+    #[private]
+    pub fn check_owner(&self) {
+        if self.owner_id != env::predecessor_account_id() {
+            panic_json(QuizError::NotOwner);
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use std::panic::PanicHookInfo;
+
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::{testing_env};
+
+    fn get_context(signer: AccountId, is_view: bool) -> VMContextBuilder {
+        let mut builder = VMContextBuilder::new();
+        builder.predecessor_account_id(signer);
+        builder.is_view(is_view);
+        // Enough to escrow the prizes of any quiz the tests create; tests that care about deposits set their own.
+        builder.attached_deposit(10u128.pow(25));
+        builder
+    }
+
+    // NEAR transfers are followed by an on_prize_transfer callback that most tests don't care about.
+    fn transfer_receipts() -> Vec<near_sdk::mock::Receipt> {
+        near_sdk::test_utils::get_created_receipts()
+            .into_iter()
+            .filter(|receipt| receipt.actions.iter().all(|action| matches!(action, near_sdk::mock::VmAction::Transfer { .. })))
+            .collect()
+    }
+
+    #[test]
+    fn create_new_contract() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let contract = QuizContract::new(account_id);
+        assert_eq!(contract.owner_id, env::signer_account_id());
+    }
+
+    #[test]
+    fn get_published_quizzes_empty() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), true);
+        testing_env!(context.build());
+
+        let contract = QuizContract::new(account_id);
+
+        let published_quizzes = contract.get_published_quizzes(0, 10);
+
+        assert_eq!(published_quizzes.quizzes.len(), 0);
+    }
+
+    #[test]
+    fn get_published_quizzes() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+
+        let published_quizzes = contract.get_published_quizzes(0, 10);
+        assert_eq!(published_quizzes.quizzes.len(), 1);
+        assert_eq!(published_quizzes.quizzes[0].quiz_id, quiz_id);
+    }
+
+    #[test]
+    fn create_new_quiz() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+
+        let quiz = contract.quizzes.get(&quiz_id).unwrap();
+        assert_eq!(quiz.question, "What is the capital of France".to_owned());
+    }
+
+    #[test]
+    #[should_panic]
+    fn crate_quiz_only_by_owner() {
+        let f  = |_: &PanicHookInfo| {};
+        std::panic::set_hook(Box::new(f));
+
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+
+        let alice = AccountId::new_unchecked("alice.near".to_owned());
+
+        let context = get_context(alice, false);
+        testing_env!(context.build());
+
+        contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+    }
+
+    #[test]
+    fn get_publish_status() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, false, None);
+        assert_eq!(contract.get_quiz_status(quiz_id).unwrap(), QuizStatus::Unpublished);
+
+        let quiz_id = contract.create_quiz("What is the capital of Germany".to_owned(), vec!["dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2".to_owned()], Some(U128(1)), None, true, None);
+        assert_eq!(contract.get_quiz_status(quiz_id).unwrap(), QuizStatus::Published);
+    }
+
+    #[test]
+    #[should_panic]
+    fn submit_answer_to_unpublished() {
+        let f  = |_: &PanicHookInfo| {};
+        std::panic::set_hook(Box::new(f));
+        
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, false, None);
+
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+    }
+
+    #[test]
+    fn submit_correct_answer_to_published() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id.clone());
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+
+        assert!(contract.solved_quizzes.get(&account_id).unwrap().contains(&quiz_id));
+    }
+
+    #[test]
+    fn submit_incorrect_answer_to_published() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id.clone());
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+
+        contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+
+        assert_eq!(contract.retries_left.get(&account_id).unwrap().get(&quiz_id).unwrap().retries_left, 2);
+        contract.submit_answer(quiz_id, "Madrid".to_owned(), None);
+        assert_eq!(contract.retries_left.get(&account_id).unwrap().get(&quiz_id).unwrap().retries_left, 1);
+    }
+
+    #[test]
+    fn publish_unpublished_quiz() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id.clone());
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, false, None);
+
+        assert_eq!(contract.quizzes.get(&quiz_id).unwrap().status, QuizStatus::Unpublished);
+        contract.publish_quiz(quiz_id);
+        assert_eq!(contract.quizzes.get(&quiz_id).unwrap().status, QuizStatus::Published);
+    }
+
+    #[test]
+    fn share_draft_with_reviewer() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let reviewer = AccountId::new_unchecked("alice.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, false, None);
+        contract.share_draft(quiz_id, reviewer.clone());
+
+        let context = get_context(reviewer, false);
+        testing_env!(context.build());
+
+        let draft = contract.get_shared_draft(quiz_id);
+        assert_eq!(draft.quiz_id, quiz_id);
+        assert_eq!(draft.question, "What is the capital of France".to_owned());
+        assert_eq!(draft.status, QuizStatus::Unpublished);
+    }
+
+    #[test]
+    #[should_panic(expected = "This draft is not shared with you")]
+    fn get_shared_draft_by_non_reviewer() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, false, None);
+        contract.share_draft(quiz_id, AccountId::new_unchecked("alice.near".to_owned()));
+
+        let context = get_context(AccountId::new_unchecked("carol.near".to_owned()), false);
+        testing_env!(context.build());
+
+        contract.get_shared_draft(quiz_id);
+    }
+
+    #[test]
+    fn revoke_draft_share() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let reviewer = AccountId::new_unchecked("alice.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, false, None);
+        contract.share_draft(quiz_id, reviewer.clone());
+        contract.revoke_draft_share(quiz_id, reviewer);
+
+        assert!(contract.draft_reviewers.get(&quiz_id).is_none());
+    }
+
+    #[test]
+    fn publish_clears_draft_shares() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, false, None);
+        contract.share_draft(quiz_id, AccountId::new_unchecked("alice.near".to_owned()));
+        contract.publish_quiz(quiz_id);
+
+        assert!(contract.draft_reviewers.get(&quiz_id).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot share a published quiz")]
+    fn share_published_quiz() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+        contract.share_draft(quiz_id, AccountId::new_unchecked("alice.near".to_owned()));
+    }
+
+    #[test]
+    fn delete_quiz() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+        empty_escrow(&mut contract, quiz_id);
+        contract.delete_quiz(quiz_id);
+
+        assert!(contract.quizzes.get(&quiz_id).is_none());
+        assert!(contract.deleted_quiz_ids.contains(&quiz_id));
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes.len(), 0);
+        assert_eq!(contract_error(|| { contract.get_quiz_status(quiz_id); }), QuizError::QuizDeleted { quiz_id });
+        assert_eq!(contract.get_quiz_status(quiz_id + 1), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Refund the escrow, NFT prize, stakes and entry fees before deleting this quiz")]
+    fn delete_funded_quiz() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.attached_deposit(100).build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        contract.delete_quiz(quiz_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Refund the escrow, NFT prize, stakes and entry fees before deleting this quiz")]
+    fn delete_quiz_holding_stakes() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.attached_deposit(100).build());
+
+        let mut contract = QuizContract::new(account_id.clone());
+        let options = QuizOptions { stake_required: Some(U128(50)), ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("alice.near".to_owned())).attached_deposit(50).build());
+        contract.stake_for_quiz(quiz_id);
+
+        testing_env!(context.predecessor_account_id(account_id).attached_deposit(0).build());
+        empty_escrow(&mut contract, quiz_id);
+        contract.delete_quiz(quiz_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "This quiz was deleted")]
+    fn submit_answer_to_deleted_quiz() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+        empty_escrow(&mut contract, quiz_id);
+        contract.delete_quiz(quiz_id);
+
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+    }
+
+    #[test]
+    fn deleted_quiz_state_does_not_leak_into_new_quiz() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id.clone());
+        let old_quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+        contract.submit_answer(old_quiz_id, "Paris".to_owned(), None);
+        empty_escrow(&mut contract, old_quiz_id);
+        contract.delete_quiz(old_quiz_id);
+
+        let new_quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+        assert_ne!(new_quiz_id, old_quiz_id);
+
+        contract.submit_answer(new_quiz_id, "Paris".to_owned(), None);
+        assert!(contract.solved_quizzes.get(&account_id).unwrap().contains(&new_quiz_id));
+    }
+
+    #[test]
+    #[should_panic(expected = "belongs to a deleted quiz")]
+    fn create_quiz_with_tombstoned_id() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+        empty_escrow(&mut contract, quiz_id);
+        contract.delete_quiz(quiz_id);
+
+        contract.current_quiz_id = quiz_id;
+        contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+    }
+
+    #[test]
+    fn epoch_locked_quiz_captures_epoch_on_publish() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.epoch_height(10).build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, false, Some(QuizOptions { epoch_locked: true, ..Default::default() }));
+        assert_eq!(contract.quizzes.get(&quiz_id).unwrap().epoch_height, None);
+
+        testing_env!(context.epoch_height(12).build());
+        contract.publish_quiz(quiz_id);
+        assert_eq!(contract.quizzes.get(&quiz_id).unwrap().epoch_height, Some(12));
+
+        let published_quizzes = contract.get_published_quizzes(0, 10);
+        assert!(!published_quizzes.quizzes[0].stale);
+
+        testing_env!(context.epoch_height(13).build());
+        let published_quizzes = contract.get_published_quizzes(0, 10);
+        assert!(published_quizzes.quizzes[0].stale);
+    }
+
+    #[test]
+    #[should_panic(expected = "This quiz was for a previous epoch")]
+    fn submit_answer_to_epoch_locked_quiz_in_later_epoch() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.epoch_height(10).build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, Some(QuizOptions { epoch_locked: true, ..Default::default() }));
+
+        testing_env!(context.epoch_height(11).build());
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+    }
+
+    #[test]
+    fn roll_epoch_quiz_starts_new_round() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.epoch_height(10).build());
+
+        let mut contract = QuizContract::new(account_id.clone());
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(QuizOptions { epoch_locked: true, max_winners: Some(1), ..Default::default() }));
+        assert_eq!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).amount, Some(U128(100)));
+
+        testing_env!(context.epoch_height(11).build());
+        contract.roll_epoch_quiz(quiz_id, "dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2".to_owned(), "What is the capital of Germany".to_owned());
+
+        let quiz = contract.quizzes.get(&quiz_id).unwrap();
+        assert_eq!(quiz.epoch_height, Some(11));
+        assert_eq!(quiz.round, 1);
+        assert_eq!((quiz.solver_count, quiz.winner_count, quiz.first_solver), (0, 0, None));
+        assert!(!contract.get_published_quizzes(0, 10).quizzes[0].stale);
+
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+        let record = contract.retries_left.get(&account_id).unwrap().get(&quiz_id).unwrap();
+        assert!(!contract.solved_quizzes.get(&account_id).unwrap().contains(&quiz_id));
+        assert_eq!(record, RetryRecord { round: 1, retries_left: 2, insurance: None, hint_used: false, last_failed_at: Some(0), step: 0 });
+
+        let result = contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+        assert!(!result.prize_pool_exhausted);
+        assert_eq!(result.amount, Some(U128(50)));
+        assert!(contract.solved_quizzes.get(&account_id).unwrap().contains(&quiz_id));
+    }
+
+    #[test]
+    fn question_parts_revealed_over_time() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_timestamp(0).build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions {
+            question_parts: vec!["I am a city".to_owned(), "on the Seine".to_owned(), "with an iron tower".to_owned()],
+            reveal_interval_sec: 60,
+            ..Default::default()
+        };
+        contract.create_quiz("".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, Some(options));
+
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes[0].question, "I am a city");
+
+        testing_env!(context.block_timestamp(60 * NANOS_PER_SEC).build());
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes[0].question, "I am a city on the Seine");
+
+        testing_env!(context.block_timestamp(300 * NANOS_PER_SEC).build());
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes[0].question, "I am a city on the Seine with an iron tower");
+    }
+
+    #[test]
+    fn earlier_stage_solves_pay_more() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_timestamp(0).build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions {
+            question_parts: vec!["I am a city".to_owned(), "on the Seine".to_owned()],
+            reveal_interval_sec: 60,
+            stage_payout_bps: vec![20_000, 10_000],
+            ..Default::default()
+        };
+        let quiz_id = contract.create_quiz("".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1000)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("alice.near".to_owned())).build());
+        let early = contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("carol.near".to_owned())).block_timestamp(60 * NANOS_PER_SEC).build());
+        let late = contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+
+        assert_eq!(early.amount, Some(U128(2000)));
+        assert_eq!(late.amount, Some(U128(1000)));
+    }
+
+    #[test]
+    fn submit_answer_for_approved_beneficiary() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let beneficiary = AccountId::new_unchecked("alice.near".to_owned());
+        let relayer = AccountId::new_unchecked("dao.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(beneficiary.clone()).build());
+        contract.approve_submitter(relayer.clone());
+
+        testing_env!(context.predecessor_account_id(relayer.clone()).build());
+        contract.submit_answer_for(quiz_id, "Paris".to_owned(), beneficiary.clone());
+
+        assert!(contract.solved_quizzes.get(&beneficiary).unwrap().contains(&quiz_id));
+        assert!(contract.solved_quizzes.get(&relayer).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "dao.near is not approved to submit answers for alice.near")]
+    fn submit_answer_for_unapproved_beneficiary() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("dao.near".to_owned())).build());
+        contract.submit_answer_for(quiz_id, "Paris".to_owned(), AccountId::new_unchecked("alice.near".to_owned()));
+    }
+
+    #[test]
+    #[should_panic(expected = "dao.near is not approved to submit answers for alice.near")]
+    fn submit_answer_for_revoked_submitter() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let beneficiary = AccountId::new_unchecked("alice.near".to_owned());
+        let relayer = AccountId::new_unchecked("dao.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(beneficiary.clone()).build());
+        contract.approve_submitter(relayer.clone());
+        contract.revoke_submitter(relayer.clone());
+
+        testing_env!(context.predecessor_account_id(relayer).build());
+        contract.submit_answer_for(quiz_id, "Paris".to_owned(), beneficiary);
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate question (see quiz 0)")]
+    fn create_duplicate_question() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+        contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate question (see quiz 0)")]
+    fn create_duplicate_question_with_different_case_and_whitespace() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+        contract.create_quiz("  what is the CAPITAL of france ".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+    }
+
+    #[test]
+    fn create_duplicate_question_when_allowed() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let first_quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+        let options = QuizOptions { allow_duplicate: true, ..Default::default() };
+        contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, Some(options));
+
+        assert_eq!(contract.question_index.get(&question_key("What is the capital of France")), Some(first_quiz_id));
+    }
+
+    #[test]
+    fn delete_quiz_frees_question() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+        empty_escrow(&mut contract, quiz_id);
+        contract.delete_quiz(quiz_id);
+
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+        assert_eq!(contract.question_index.get(&question_key("What is the capital of France")), Some(quiz_id));
+    }
+
+    #[test]
+    fn archived_question_can_be_created_again() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let archived = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+        contract.archive_quiz(archived);
+
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+        assert_ne!(quiz_id, archived);
+        assert_eq!(contract.question_index.get(&question_key("What is the capital of France")), Some(quiz_id));
+    }
+
+    #[test]
+    fn get_quiz_page_matches_individual_views() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let participant = AccountId::new_unchecked("alice.near".to_owned());
+        let fresh_account = AccountId::new_unchecked("carol.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(participant.clone()).build());
+        contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+
+        let published = &contract.get_published_quizzes(0, 10).quizzes[0];
+        let status = contract.get_quiz_status(quiz_id).unwrap();
+
+        let page = contract.get_quiz_page(quiz_id, Some(participant.clone())).unwrap();
+        assert_eq!(page.question, published.question);
+        assert_eq!(page.prize_amount, published.prize_amount);
+        assert_eq!(page.status, status);
+        assert_eq!(page.solver_count, 0);
+        assert_eq!(page.retries_left, Some(contract.retries_left.get(&participant).unwrap().get(&quiz_id).unwrap().retries_left));
+        assert_eq!(page.solved, Some(false));
+
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+        let page = contract.get_quiz_page(quiz_id, Some(participant.clone())).unwrap();
+        assert_eq!(page.solved, Some(contract.solved_quizzes.get(&participant).unwrap().contains(&quiz_id)));
+        assert_eq!(page.solver_count, 1);
+
+        let page = contract.get_quiz_page(quiz_id, Some(fresh_account)).unwrap();
+        assert_eq!(page.retries_left, Some(DEFAULT_RETRIES));
+        assert_eq!(page.solved, Some(false));
+
+        let page = contract.get_quiz_page(quiz_id, None).unwrap();
+        assert_eq!(page.retries_left, None);
+        assert_eq!(page.solved, None);
+        assert!(!near_sdk::serde_json::to_string(&page).unwrap().contains("5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1"));
+    }
+
+    #[test]
+    fn get_quiz_page_hides_drafts_and_missing_quizzes() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, false, None);
+
+        assert!(contract.get_quiz_page(quiz_id, None).is_none());
+        assert!(contract.get_quiz_page(quiz_id + 1, None).is_none());
+    }
+
+    #[test]
+    fn payout_record_lists_applied_modifiers() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_timestamp(0).build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions {
+            question_parts: vec!["I am a city".to_owned(), "on the Seine".to_owned()],
+            reveal_interval_sec: 60,
+            stage_payout_bps: vec![15_000, 10_000],
+            ..Default::default()
+        };
+        let quiz_id = contract.create_quiz("".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1000)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(player.clone()).block_index(42).build());
+        contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+
+        let records = contract.get_payout_records(0, 10);
+        assert_eq!(records, vec![PayoutRecord {
+            quiz_id,
+            account_id: player.clone(),
+            recipient: player.clone(),
+            attempt_number: 2,
+            base_prize: U128(1000),
+            modifiers_applied: vec![("stage_multiplier".to_owned(), 5_000), ("retry_decay".to_owned(), -5_000)],
+            final_amount: U128(750),
+            treasury_fee: U128(0),
+            token: None,
+            block_height: 42,
+            round: 0
+        }]);
+        assert_eq!(contract.get_payouts_for(player, 0, 10), records);
+        assert!(contract.get_payouts_for(AccountId::new_unchecked("carol.near".to_owned()), 0, 10).is_empty());
+    }
+
+    #[test]
+    fn create_quiz_with_prize_in_near() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], None, Some("1.5".to_owned()), true, None);
+
+        assert_eq!(contract.quizzes.get(&quiz_id).unwrap().max_prize_amount, 1_500_000_000_000_000_000_000_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "more than 24 fractional digits")]
+    fn create_quiz_with_too_precise_prize_in_near() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], None, Some("0.0000000000000000000000001".to_owned()), true, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Provide exactly one of max_prize_amount or max_prize_near")]
+    fn create_quiz_with_both_prize_params() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), Some("1".to_owned()), true, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Provide exactly one of max_prize_amount or max_prize_near")]
+    fn create_quiz_without_prize_params() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], None, None, true, None);
+    }
+
+    #[test]
+    fn global_attempt_cap_boundary() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { global_attempt_cap: Some(3), ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("alice.near".to_owned())).build());
+        contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+        contract.submit_answer(quiz_id, "Madrid".to_owned(), None);
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes[0].global_attempts_remaining, Some(1));
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("carol.near".to_owned())).build());
+        let result = contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+        assert!(result.correct);
+        assert_eq!(result.amount, Some(U128(1)));
+
+        let json_quiz = &contract.get_published_quizzes(0, 10).quizzes[0];
+        assert_eq!(json_quiz.global_attempts_remaining, Some(0));
+        assert!(json_quiz.guess_budget_exhausted);
+    }
+
+    #[test]
+    #[should_panic(expected = "guess budget exhausted")]
+    fn global_attempt_cap_rejects_attempts_past_cap() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { global_attempt_cap: Some(1), ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("alice.near".to_owned())).build());
+        contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+    }
+
+    #[test]
+    fn submit_answer_with_payout_to() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+        let charity = AccountId::new_unchecked("charity.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1000)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(0).build());
+        contract.submit_answer(quiz_id, "Paris".to_owned(), Some(charity.clone()));
+
+        assert!(contract.solved_quizzes.get(&player).unwrap().contains(&quiz_id));
+        assert!(contract.solved_quizzes.get(&charity).is_none());
+
+        let receipts = transfer_receipts();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].receiver_id, charity);
+        assert_eq!(receipts[0].actions, vec![near_sdk::mock::VmAction::Transfer { deposit: 1000 }]);
+
+        let record = &contract.get_payouts_for(player.clone(), 0, 1)[0];
+        assert_eq!(record.account_id, player);
+        assert_eq!(record.recipient, charity);
+
+        let logs = near_sdk::test_utils::get_logs();
+        assert!(logs[0].starts_with("EVENT_JSON:"));
+        assert!(logs[0].contains(r#""account_id":"alice.near""#));
+        assert!(logs[0].contains(r#""recipient":"charity.near""#));
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot pay a prize out to the quiz contract itself")]
+    fn submit_answer_with_payout_to_contract() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("alice.near".to_owned())).build());
+        contract.submit_answer(quiz_id, "Paris".to_owned(), Some(env::current_account_id()));
+    }
+
+    #[test]
+    fn buy_and_extend_season_pass() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_timestamp(0).build());
+
+        let mut contract = QuizContract::new(account_id);
+        contract.set_season_pass_config(U128(100), 10, Some(25 * NANOS_PER_SEC));
+
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(150).block_timestamp(NANOS_PER_SEC).build());
+        assert_eq!(contract.buy_season_pass(), 11 * NANOS_PER_SEC);
+        assert_eq!(contract.has_season_pass(player.clone()), Some(11 * NANOS_PER_SEC));
+        assert_eq!(contract.get_withdrawable_balance(), U128(100));
+
+        let receipts = transfer_receipts();
+        assert_eq!(receipts[0].receiver_id, player);
+        assert_eq!(receipts[0].actions, vec![near_sdk::mock::VmAction::Transfer { deposit: 50 }]);
+
+        testing_env!(context.attached_deposit(100).block_timestamp(5 * NANOS_PER_SEC).build());
+        assert_eq!(contract.buy_season_pass(), 21 * NANOS_PER_SEC);
+
+        testing_env!(context.block_timestamp(20 * NANOS_PER_SEC).build());
+        assert_eq!(contract.buy_season_pass(), 25 * NANOS_PER_SEC);
+        assert_eq!(contract.get_withdrawable_balance(), U128(300));
+    }
+
+    #[test]
+    fn season_pass_expires() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_timestamp(0).build());
+
+        let mut contract = QuizContract::new(account_id.clone());
+        contract.set_season_pass_config(U128(100), 10, None);
+
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(100).build());
+        contract.buy_season_pass();
+
+        testing_env!(context.block_timestamp(10 * NANOS_PER_SEC - 1).build());
+        assert_eq!(contract.has_season_pass(player.clone()), Some(10 * NANOS_PER_SEC));
+
+        testing_env!(context.block_timestamp(10 * NANOS_PER_SEC).build());
+        assert_eq!(contract.has_season_pass(player.clone()), None);
+    }
+
+    #[test]
+    fn roll_season_invalidates_passes() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_timestamp(0).build());
+
+        let mut contract = QuizContract::new(account_id.clone());
+        contract.set_season_pass_config(U128(100), 10, None);
+
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(100).build());
+        contract.buy_season_pass();
+
+        testing_env!(context.predecessor_account_id(account_id).attached_deposit(0).build());
+        contract.roll_season(None);
+
+        assert_eq!(contract.has_season_pass(player), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Attach at least 100 yoctoNEAR to buy a season pass")]
+    fn buy_season_pass_with_insufficient_deposit() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        contract.set_season_pass_config(U128(100), 10, None);
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("alice.near".to_owned())).attached_deposit(99).build());
+        contract.buy_season_pass();
+    }
+
+    #[test]
+    fn get_published_quizzes_skips_dangling_ids() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let dangling_quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1)), None, true, None);
+        let quiz_id = contract.create_quiz("What is the capital of Germany".to_owned(), vec!["dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2".to_owned()], Some(U128(1)), None, true, None);
+        contract.quizzes.remove(&dangling_quiz_id);
+
+        let published_quizzes = contract.get_published_quizzes(0, 10);
+        assert_eq!(published_quizzes.quizzes.len(), 1);
+        assert_eq!(published_quizzes.quizzes[0].quiz_id, quiz_id);
+        assert_eq!(published_quizzes.dangling_ids, vec![dangling_quiz_id]);
+
+        assert_eq!(contract.repair_published_index(), vec![dangling_quiz_id]);
+
+        let published_quizzes = contract.get_published_quizzes(0, 10);
+        assert_eq!(published_quizzes.quizzes.len(), 1);
+        assert!(published_quizzes.dangling_ids.is_empty());
+    }
+
+    #[test]
+    fn escalating_attempt_fees() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { fee_schedule: vec![U128(0), U128(10), U128(100)], ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1000)), None, true, Some(options));
+
+        assert_eq!(contract.get_next_attempt_fee(quiz_id, player.clone()), U128(0));
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(5).build());
+        contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+        assert_eq!(transfer_receipts()[0].actions, vec![near_sdk::mock::VmAction::Transfer { deposit: 5 }]);
+
+        assert_eq!(contract.get_next_attempt_fee(quiz_id, player.clone()), U128(10));
+        testing_env!(context.attached_deposit(10).build());
+        contract.submit_answer(quiz_id, "Madrid".to_owned(), None);
+        assert!(transfer_receipts().is_empty());
+
+        assert_eq!(contract.get_next_attempt_fee(quiz_id, player.clone()), U128(100));
+        testing_env!(context.attached_deposit(150).build());
+        let result = contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+        assert!(result.correct);
+
+        let receipts = transfer_receipts();
+        assert!(receipts.iter().any(|receipt| receipt.actions == vec![near_sdk::mock::VmAction::Transfer { deposit: 50 }]));
+        assert_eq!(contract.get_withdrawable_balance(), U128(110));
+    }
+
+    #[test]
+    #[should_panic(expected = "This attempt costs 10 yoctoNEAR")]
+    fn attempt_fee_enforced_without_season_pass() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { fee_schedule: vec![U128(10), U128(10), U128(10)], ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1000)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("alice.near".to_owned())).attached_deposit(9).build());
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+    }
+
+    #[test]
+    fn attempt_fee_skipped_with_season_pass() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        contract.set_season_pass_config(U128(100), 3600, None);
+        let options = QuizOptions { fee_schedule: vec![U128(10), U128(10), U128(10)], ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1000)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(100).build());
+        contract.buy_season_pass();
+
+        assert_eq!(contract.get_next_attempt_fee(quiz_id, player), U128(0));
+        testing_env!(context.attached_deposit(0).build());
+        assert!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).correct);
+    }
+
+    #[test]
+    #[should_panic(expected = "fee_schedule must have one entry per attempt (3)")]
+    fn create_quiz_with_wrong_fee_schedule_length() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { fee_schedule: vec![U128(0), U128(10)], ..Default::default() };
+        contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1000)), None, true, Some(options));
+    }
+
+    #[test]
+    fn treasury_fee_split() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+        let treasury = AccountId::new_unchecked("treasury.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        contract.set_treasury(treasury.clone(), 250);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(999)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(0).build());
+        let result = contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+        assert_eq!(result.amount, Some(U128(975)));
+
+        let receipts = transfer_receipts();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].receiver_id, player);
+        assert_eq!(receipts[0].actions, vec![near_sdk::mock::VmAction::Transfer { deposit: 975 }]);
+
+        let record = &contract.get_payout_records(0, 1)[0];
+        assert_eq!(record.final_amount, U128(999));
+        assert_eq!(record.treasury_fee, U128(24));
+        assert_eq!(contract.get_accrued_fees(), AccruedFees { near: U128(24), tokens: vec![] });
+
+        testing_env!(context.predecessor_account_id(treasury.clone()).build());
+        assert_eq!(contract.withdraw_treasury(None), U128(24));
+        let receipts = transfer_receipts();
+        assert_eq!(receipts.last().unwrap().receiver_id, treasury);
+        assert_eq!(receipts.last().unwrap().actions, vec![near_sdk::mock::VmAction::Transfer { deposit: 24 }]);
+        assert_eq!(contract_error(|| { contract.withdraw_treasury(None); }), QuizError::NothingToWithdraw);
+    }
+
+    #[test]
+    fn set_fee_changes_treasury_cut() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        contract.set_treasury(AccountId::new_unchecked("treasury.near".to_owned()), 250);
+        contract.set_fee(500);
+        assert_eq!(contract.get_treasury().unwrap().fee_bps, 500);
+        assert_eq!(contract_error(|| contract.set_fee(2_000)), QuizError::TreasuryFeeTooHigh { fee_bps: 2_000, max_bps: 1_000 });
+    }
+
+    #[test]
+    fn zero_treasury_fee_pays_solver_in_full() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        contract.set_treasury(AccountId::new_unchecked("treasury.near".to_owned()), 0);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(999)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(0).build());
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+
+        let receipts = transfer_receipts();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].receiver_id, player);
+        assert_eq!(receipts[0].actions, vec![near_sdk::mock::VmAction::Transfer { deposit: 999 }]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Treasury fee cannot exceed 1000 bps")]
+    fn set_treasury_above_cap() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        contract.set_treasury(AccountId::new_unchecked("treasury.near".to_owned()), 1001);
+    }
+
+    #[test]
+    fn small_wins_accrue_until_threshold() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let france = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+        let germany = contract.create_quiz("What is the capital of Germany".to_owned(), vec!["dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2".to_owned()], Some(U128(100)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(0).build());
+        contract.set_payout_preference(U128(150));
+        contract.submit_answer(france, "Paris".to_owned(), None);
+        assert!(transfer_receipts().is_empty());
+        assert_eq!(contract.get_payout_preference(player.clone()), PayoutPreference { min_transfer: U128(150), accrued: U128(100) });
+
+        testing_env!(context.predecessor_account_id(player.clone()).build());
+        contract.submit_answer(germany, "Berlin".to_owned(), None);
+
+        let receipts = transfer_receipts();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].receiver_id, player);
+        assert_eq!(receipts[0].actions, vec![near_sdk::mock::VmAction::Transfer { deposit: 200 }]);
+        assert_eq!(contract.get_payout_preference(player).accrued, U128(0));
+    }
+
+    #[test]
+    fn claim_prizes_drains_accrued() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(player.clone()).build());
+        contract.set_payout_preference(U128(150));
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+
+        testing_env!(context.predecessor_account_id(player.clone()).build());
+        assert_eq!(contract.claim_prizes(), U128(100));
+
+        let receipts = transfer_receipts();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].receiver_id, player);
+        assert_eq!(receipts[0].actions, vec![near_sdk::mock::VmAction::Transfer { deposit: 100 }]);
+        assert_eq!(contract.get_payout_preference(player).accrued, U128(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Nothing to claim")]
+    fn claim_prizes_with_nothing_accrued() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        contract.claim_prizes();
+    }
+
+    #[test]
+    fn practice_answers_leave_no_trace() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(player.clone()).build());
+        assert_eq!(contract.submit_practice_answer(quiz_id, "Berlin".to_owned()), PracticeResult { correct: false });
+        assert_eq!(contract.submit_practice_answer(quiz_id, "Paris".to_owned()), PracticeResult { correct: true });
+
+        assert!(transfer_receipts().is_empty());
+        assert!(near_sdk::test_utils::get_logs().iter().all(|log| !log.starts_with("EVENT_JSON:")));
+        assert!(contract.get_payout_records(0, 10).is_empty());
+
+        let page = contract.get_quiz_page(quiz_id, Some(player)).unwrap();
+        assert_eq!(page.retries_left, Some(DEFAULT_RETRIES));
+        assert_eq!(page.solved, Some(false));
+        assert_eq!(page.solver_count, 0);
+        assert_eq!(page.practice_attempts, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Practice is disabled for this quiz")]
+    fn practice_disabled_quiz() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { practice_disabled: true, ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options));
+
+        contract.submit_practice_answer(quiz_id, "Paris".to_owned());
+    }
+
+    #[test]
+    fn stale_boost_shows_in_views_and_payout() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_timestamp(0).build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions {
+            stale_policy: Some(StalePolicy { after_sec: 60, prize_increase_bps: 5_000, max_increases: 2 }),
+            ..Default::default()
+        };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1000)), None, true, Some(options));
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes[0].prize_amount, U128(1000));
+
+        testing_env!(context.predecessor_account_id(player).block_timestamp(90 * NANOS_PER_SEC).build());
+        assert_eq!(contract.get_quiz_page(quiz_id, None).unwrap().prize_amount, U128(1500));
+
+        let result = contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+        assert_eq!(result.amount, Some(U128(1500)));
+        assert_eq!(contract.get_payout_records(0, 1)[0].modifiers_applied, vec![("stale_boost".to_owned(), 5_000)]);
+
+        testing_env!(context.block_timestamp(600 * NANOS_PER_SEC).build());
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes[0].prize_amount, U128(1500));
+    }
+
+    #[test]
+    fn stale_boost_view_stops_at_escrow() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_timestamp(0).attached_deposit(1200).build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions {
+            stale_policy: Some(StalePolicy { after_sec: 60, prize_increase_bps: 5_000, max_increases: 2 }),
+            ..Default::default()
+        };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1000)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(player).block_timestamp(90 * NANOS_PER_SEC).attached_deposit(0).build());
+        assert_eq!(contract.get_quiz_page(quiz_id, None).unwrap().prize_amount, U128(1200));
+        assert_eq!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).amount, Some(U128(1200)));
+    }
+
+    #[test]
+    fn shutdown_walks_through_phases() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_timestamp(0).build());
+
+        let mut contract = QuizContract::new(account_id.clone());
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+        assert_eq!(contract.get_shutdown_status(), ShutdownStatus { phase: ContractPhase::Active, closes_at: None });
+
+        assert_eq!(contract.initiate_shutdown(60), 60 * NANOS_PER_SEC);
+        assert_eq!(contract.get_shutdown_status().phase, ContractPhase::ShuttingDown);
+
+        testing_env!(context.predecessor_account_id(player.clone()).block_timestamp(30 * NANOS_PER_SEC).build());
+        contract.set_payout_preference(U128(150));
+        assert!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).correct);
+
+        testing_env!(context.predecessor_account_id(player.clone()).block_timestamp(60 * NANOS_PER_SEC).build());
+        assert_eq!(contract.get_shutdown_status(), ShutdownStatus { phase: ContractPhase::Closed, closes_at: Some(60 * NANOS_PER_SEC) });
+        assert_eq!(contract.claim_prizes(), U128(100));
+    }
+
+    #[test]
+    fn closed_contract_refunds_escrow_stakes_and_entry_fees() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_timestamp(0).attached_deposit(100).build());
+
+        let mut contract = QuizContract::new(account_id.clone());
+        let options = QuizOptions {
+            stake_required: Some(U128(50)),
+            fee_schedule: vec![U128(10), U128(10), U128(10)],
+            entry_refund_rake_bps: Some(0),
+            ..Default::default()
+        };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(50).build());
+        contract.stake_for_quiz(quiz_id);
+        testing_env!(context.attached_deposit(10).build());
+        assert!(!contract.submit_answer(quiz_id, "Berlin".to_owned(), None).correct);
+
+        testing_env!(context.predecessor_account_id(account_id.clone()).attached_deposit(0).build());
+        contract.initiate_shutdown(60);
+        assert_eq!(contract_error(|| { contract.refund_quiz(quiz_id); }), QuizError::EscrowLocked { quiz_id });
+
+        testing_env!(context.block_timestamp(60 * NANOS_PER_SEC).build());
+        assert_eq!(contract.refund_quiz(quiz_id), U128(100));
+
+        testing_env!(context.predecessor_account_id(player).build());
+        assert_eq!(contract.withdraw_stake(quiz_id), U128(50));
+        assert_eq!(contract.refund_entry(quiz_id), U128(10));
+    }
+
+    #[test]
+    #[should_panic(expected = "Not allowed while the contract is ShuttingDown")]
+    fn shutdown_stops_quiz_creation() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        contract.initiate_shutdown(60);
+        contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Not allowed while the contract is ShuttingDown")]
+    fn shutdown_stops_publication() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, false, None);
+        contract.initiate_shutdown(60);
+        contract.publish_quiz(quiz_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Not allowed while the contract is Closed")]
+    fn closed_contract_rejects_answers() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_timestamp(0).build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+        contract.initiate_shutdown(60);
+
+        testing_env!(context.block_timestamp(61 * NANOS_PER_SEC).build());
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+    }
+
+    #[test]
+    fn closed_contract_still_pays_out_balances() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_timestamp(0).build());
+
+        let mut contract = QuizContract::new(account_id.clone());
+        contract.set_season_pass_config(U128(10), 60, None);
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("alice.near".to_owned())).attached_deposit(10).build());
+        contract.buy_season_pass();
+
+        testing_env!(context.predecessor_account_id(account_id).attached_deposit(0).build());
+        contract.initiate_shutdown(0);
+        assert_eq!(contract.get_shutdown_status().phase, ContractPhase::Closed);
+        assert_eq!(contract.withdraw_balance(), U128(10));
+    }
+
+    fn quiz_with_hints(contract: &mut QuizContract) -> QuizId {
+        let options = QuizOptions {
+            feedback_hashes: Some(FeedbackHints { length: 5, first_char_hash: util::first_char_hash("Paris") }),
+            ..Default::default()
+        };
+        contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options))
+    }
+
+    #[test]
+    fn feedback_on_close_answer() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = quiz_with_hints(&mut contract);
+
+        let result = contract.submit_answer(quiz_id, "Pariz".to_owned(), None);
+        assert_eq!(result.feedback, Some(AnswerFeedback { length_matches: true, first_char_matches: true }));
+    }
+
+    #[test]
+    fn feedback_on_distant_answer() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = quiz_with_hints(&mut contract);
+
+        let result = contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+        assert_eq!(result.feedback, Some(AnswerFeedback { length_matches: false, first_char_matches: false }));
+    }
+
+    #[test]
+    fn no_feedback_without_hints() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        let result = contract.submit_answer(quiz_id, "Pariz".to_owned(), None);
+        assert!(!result.correct);
+        assert_eq!(result.feedback, None);
+    }
+
+    fn insured_quiz(contract: &mut QuizContract) -> QuizId {
+        let options = QuizOptions { insurance_price: Some(U128(10)), ..Default::default() };
+        contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options))
+    }
+
+    #[test]
+    fn insurance_keeps_retry_on_wrong_answer() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = insured_quiz(&mut contract);
+
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(10).build());
+        contract.buy_retry_insurance(quiz_id);
+
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(0).build());
+        let escrow = contract.get_quiz_funding(quiz_id).unwrap().balance.0;
+        let result = contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+        assert_eq!(result.retries_left, DEFAULT_RETRIES);
+        assert_eq!(contract.get_withdrawable_balance(), U128(0));
+        assert_eq!(contract.get_quiz_funding(quiz_id).unwrap().balance, U128(escrow + 10));
+
+        let result = contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+        assert_eq!(result.retries_left, DEFAULT_RETRIES - 1);
+    }
+
+    #[test]
+    fn insurance_refunded_on_correct_answer() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = insured_quiz(&mut contract);
+
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(10).build());
+        contract.buy_retry_insurance(quiz_id);
+
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(0).build());
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+
+        let receipts = transfer_receipts();
+        assert_eq!(receipts.len(), 2);
+        assert_eq!(receipts[0].receiver_id, player);
+        assert_eq!(receipts[0].actions, vec![near_sdk::mock::VmAction::Transfer { deposit: 10 }]);
+        assert_eq!(receipts[1].actions, vec![near_sdk::mock::VmAction::Transfer { deposit: 100 }]);
+        assert_eq!(contract.get_withdrawable_balance(), U128(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "You already hold retry insurance for this quiz")]
+    fn insurance_double_purchase() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = insured_quiz(&mut contract);
+
+        testing_env!(context.attached_deposit(10).build());
+        contract.buy_retry_insurance(quiz_id);
+        contract.buy_retry_insurance(quiz_id);
+    }
+
+    #[test]
+    fn race_pays_by_placement() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { placements: vec![U128(500), U128(300), U128(100)], ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(500)), None, true, Some(options));
+
+        let solvers = [("alice.near", 500), ("carol.near", 300), ("dave.near", 100)];
+        for (placement, (solver, prize)) in solvers.into_iter().enumerate() {
+            testing_env!(context.predecessor_account_id(AccountId::new_unchecked(solver.to_owned())).build());
+            if placement == 1 {
+                contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+            }
+            let result = contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+            assert_eq!(result.amount, Some(U128(prize)));
+            assert!(near_sdk::test_utils::get_logs().last().unwrap().contains(&format!("\"placement\":{}", placement)));
+        }
+
+        let json_quiz = &contract.get_published_quizzes(0, 10).quizzes[0];
+        assert_eq!(json_quiz.remaining_placements, Some(vec![]));
+        assert_eq!(contract.get_payout_records(1, 1)[0].attempt_number, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "All placements on this quiz have been claimed")]
+    fn race_rejects_after_last_placement() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { placements: vec![U128(500)], ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(500)), None, true, Some(options));
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes[0].remaining_placements, Some(vec![U128(500)]));
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("alice.near".to_owned())).build());
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("carol.near".to_owned())).build());
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+    }
+
+    fn signing_key() -> (ed25519_dalek::ExpandedSecretKey, ed25519_dalek::PublicKey) {
+        let secret = ed25519_dalek::SecretKey::from_bytes(&[7; 32]).unwrap();
+        let public = ed25519_dalek::PublicKey::from(&secret);
+        (ed25519_dalek::ExpandedSecretKey::from(&secret), public)
+    }
+
+    fn near_public_key(public: &ed25519_dalek::PublicKey) -> PublicKey {
+        PublicKey::try_from([&[0], public.as_bytes().as_slice()].concat()).unwrap()
+    }
+
+    fn sign_answer(quiz_id: QuizId, answer: &str, nonce: u64) -> Base64VecU8 {
+        let (secret, public) = signing_key();
+        let message = util::signed_answer_message(&env::current_account_id(), quiz_id, answer, nonce);
+        Base64VecU8(secret.sign(&message, &public).to_bytes().to_vec())
+    }
+
+    #[test]
+    fn signed_submission_credits_signer() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+        let relayer = AccountId::new_unchecked("relayer.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(0).build());
+        contract.register_signing_key(near_public_key(&signing_key().1));
+
+        testing_env!(context.predecessor_account_id(relayer).build());
+        let result = contract.submit_answer_signed(quiz_id, "Paris".to_owned(), player.clone(), 1, sign_answer(quiz_id, "Paris", 1));
+        assert!(result.correct);
+
+        let receipts = transfer_receipts();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].receiver_id, player);
+        assert!(contract.solved_quizzes.get(&player).unwrap().contains(&quiz_id));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid signature")]
+    fn signed_submission_with_wrong_key() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        let other = ed25519_dalek::PublicKey::from(&ed25519_dalek::SecretKey::from_bytes(&[9; 32]).unwrap());
+        testing_env!(context.predecessor_account_id(player.clone()).build());
+        contract.register_signing_key(near_public_key(&other));
+
+        contract.submit_answer_signed(quiz_id, "Paris".to_owned(), player, 1, sign_answer(quiz_id, "Paris", 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Nonce 1 was already used")]
+    fn signed_submission_replay() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(player.clone()).build());
+        contract.register_signing_key(near_public_key(&signing_key().1));
+
+        contract.submit_answer_signed(quiz_id, "Berlin".to_owned(), player.clone(), 1, sign_answer(quiz_id, "Berlin", 1));
+        contract.submit_answer_signed(quiz_id, "Berlin".to_owned(), player, 1, sign_answer(quiz_id, "Berlin", 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "No signing key registered for alice.near")]
+    fn signed_submission_without_registered_key() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        contract.submit_answer_signed(quiz_id, "Paris".to_owned(), player, 1, sign_answer(quiz_id, "Paris", 1));
+    }
+
+    #[test]
+    fn activity_since_midpoint() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_index(10).build());
+
+        let mut contract = QuizContract::new(account_id.clone());
+        let france = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+        let options = QuizOptions { epoch_locked: true, ..Default::default() };
+        let germany = contract.create_quiz("What is the capital of Germany".to_owned(), vec!["dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2".to_owned()], Some(U128(100)), None, false, Some(options));
+
+        testing_env!(context.predecessor_account_id(player.clone()).block_index(20).build());
+        contract.submit_answer(france, "Paris".to_owned(), None);
+
+        testing_env!(context.predecessor_account_id(account_id).block_index(30).build());
+        contract.publish_quiz(germany);
+
+        testing_env!(context.block_index(40).epoch_height(1).build());
+        contract.roll_epoch_quiz(germany, "5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned(), "What is the capital of Germany again".to_owned());
+
+        testing_env!(context.block_index(50).build());
+        let digest = contract.get_activity_since(20, 10);
+        assert_eq!(digest, ActivityDigest {
+            published: vec![germany],
+            solved: vec![],
+            expired: vec![germany],
+            latest_block_height: 50
+        });
+
+        let digest = contract.get_activity_since(10, 1);
+        assert_eq!(digest.solved, vec![SolvedActivity { quiz_id: france, account_id: player, amount: U128(100) }]);
+        assert!(digest.published.is_empty());
+        assert_eq!(digest.latest_block_height, 20);
+    }
+
+    #[test]
+    fn activity_retention_prunes_oldest() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        contract.set_activity_retention(2);
+
+        let questions = [
+            ("What is the capital of France", "5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1"),
+            ("What is the capital of Germany", "dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2"),
+            ("What is the capital of Spain", "5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1")
+        ];
+        for (height, (question, hash)) in questions.into_iter().enumerate() {
+            testing_env!(context.block_index(height as u64 + 1).build());
+            contract.create_quiz(question.to_owned(), vec![hash.to_owned()], Some(U128(100)), None, true, None);
+        }
+
+        assert_eq!(contract.get_activity_since(0, 10).published, vec![1, 2]);
+
+        contract.set_activity_retention(1);
+        assert_eq!(contract.get_activity_since(0, 10).published, vec![2]);
+    }
+
+    fn wager_setup(context: &mut VMContextBuilder, player: &AccountId) -> (QuizContract, QuizId, QuizId) {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        testing_env!(context.predecessor_account_id(account_id.clone()).build());
+
+        let mut contract = QuizContract::new(account_id);
+        let france = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+        let germany = contract.create_quiz("What is the capital of Germany".to_owned(), vec!["dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2".to_owned()], Some(U128(100)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(player.clone()).build());
+        contract.set_payout_preference(U128(1_000));
+        contract.submit_answer(france, "Paris".to_owned(), None);
+        contract.wager_prize(france, germany);
+        assert_eq!(contract.get_payout_preference(player.clone()).accrued, U128(0));
+
+        (contract, france, germany)
+    }
+
+    #[test]
+    fn wager_won_on_first_attempt() {
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+        let mut context = get_context(player.clone(), false);
+        let (mut contract, france, germany) = wager_setup(&mut context, &player);
+
+        let escrow = contract.get_quiz_funding(germany).unwrap().balance.0;
+        contract.submit_answer(germany, "Berlin".to_owned(), None);
+        assert_eq!(contract.get_wager(player.clone(), france).unwrap().status, WagerStatus::Won);
+        assert_eq!(contract.get_payout_preference(player).accrued, U128(300));
+        assert_eq!(contract.get_quiz_funding(germany).unwrap().balance, U128(escrow - 200));
+        assert_eq!(contract.get_quiz_budget(germany).unwrap().paid_out, U128(200));
+    }
+
+    #[test]
+    fn wager_forfeited_after_all_retries() {
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+        let mut context = get_context(player.clone(), false);
+        let (mut contract, france, germany) = wager_setup(&mut context, &player);
+        let escrow = contract.get_quiz_funding(germany).unwrap().balance.0;
+
+        for _ in 0..DEFAULT_RETRIES {
+            contract.submit_answer(germany, "Paris".to_owned(), None);
+        }
+        assert_eq!(contract.get_wager(player.clone(), france).unwrap().status, WagerStatus::Forfeited);
+        assert_eq!(contract.get_payout_preference(player).accrued, U128(0));
+        assert_eq!(contract.get_withdrawable_balance(), U128(0));
+        assert_eq!(contract.get_quiz_funding(germany).unwrap().balance, U128(escrow + 100));
+    }
+
+    #[test]
+    fn wager_refunded_when_follow_up_closes() {
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+        let mut context = get_context(player.clone(), false);
+        let (mut contract, france, germany) = wager_setup(&mut context, &player);
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("bob.near".to_owned())).build());
+        empty_escrow(&mut contract, germany);
+        contract.delete_quiz(germany);
+
+        testing_env!(context.predecessor_account_id(player.clone()).build());
+        assert_eq!(contract.cancel_wager(), U128(100));
+        assert_eq!(contract.get_wager(player.clone(), france).unwrap().status, WagerStatus::Refunded);
+        assert_eq!(contract.get_payout_preference(player).accrued, U128(100));
+    }
+
+    #[test]
+    #[should_panic(expected = "The follow-up quiz is still open")]
+    fn wager_cannot_be_cancelled_while_follow_up_open() {
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+        let mut context = get_context(player.clone(), false);
+        let (mut contract, _, _) = wager_setup(&mut context, &player);
+
+        contract.cancel_wager();
+    }
+
+    // Stands in for the funders taking their escrow back, which delete_quiz waits for.
+    fn empty_escrow(contract: &mut QuizContract, quiz_id: QuizId) {
+        let mut quiz = contract.quizzes.get(&quiz_id).unwrap();
+        quiz.escrow = 0;
+        contract.quizzes.insert(&quiz_id, &quiz);
+    }
+
+    fn contract_error(call: impl FnOnce()) -> QuizError {
+        let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(call)).unwrap_err();
+        let panic = panic.downcast_ref::<String>().expect("panic message should be a string");
+        QuizError::from_panic_string(panic).unwrap_or_else(|| panic!("not a structured error: {}", panic))
+    }
+
+    #[test]
+    fn panics_carry_structured_errors() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { fee_schedule: vec![U128(0), U128(10), U128(10)], ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options));
+
+        assert_eq!(
+            contract_error(|| contract.set_treasury(AccountId::new_unchecked("treasury.near".to_owned()), 2_000)),
+            QuizError::TreasuryFeeTooHigh { fee_bps: 2_000, max_bps: MAX_TREASURY_FEE_BPS }
+        );
+
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(0).build());
+        assert_eq!(contract_error(|| contract.publish_quiz(quiz_id)), QuizError::NotOwner);
+        assert_eq!(contract_error(|| { contract.submit_answer(quiz_id + 1, "Paris".to_owned(), None); }), QuizError::QuizNotFound { quiz_id: quiz_id + 1 });
+        assert_eq!(contract_error(|| { contract.claim_prizes(); }), QuizError::NothingToClaim);
+
+        contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+        assert_eq!(
+            contract_error(|| { contract.submit_answer(quiz_id, "Berlin".to_owned(), None); }),
+            QuizError::InsufficientDeposit { purpose: DepositPurpose::Attempt, required: U128(10), attached: U128(0) }
+        );
+
+        testing_env!(context.predecessor_account_id(player).attached_deposit(10).build());
+        contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+        contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+        assert_eq!(
+            contract_error(|| { contract.submit_answer(quiz_id, "Paris".to_owned(), None); }),
+            QuizError::OutOfRetries { quiz_id, refresh_at: None }
+        );
+    }
+
+    #[test]
+    fn unpublish_quiz_stops_submissions_and_keeps_solves() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id.clone());
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(player.clone()).build());
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+
+        testing_env!(context.predecessor_account_id(account_id).build());
+        contract.unpublish_quiz(quiz_id);
+        assert_eq!(contract.get_quiz_status(quiz_id), Some(QuizStatus::Unpublished));
+        assert!(contract.get_published_quizzes(0, 10).quizzes.is_empty());
+        assert!(contract.solved_quizzes.get(&player).unwrap().contains(&quiz_id));
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("carol.near".to_owned())).build());
+        assert_eq!(
+            contract_error(|| { contract.submit_answer(quiz_id, "Paris".to_owned(), None); }),
+            QuizError::QuizNotPublished { quiz_id }
+        );
+    }
+
+    #[test]
+    fn delete_quiz_cleans_up_participants() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let alice = AccountId::new_unchecked("alice.near".to_owned());
+        let carol = AccountId::new_unchecked("carol.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id.clone());
+        let france = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+        let germany = contract.create_quiz("What is the capital of Germany".to_owned(), vec!["dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2".to_owned()], Some(U128(100)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(alice.clone()).build());
+        contract.submit_answer(france, "Paris".to_owned(), None);
+        contract.submit_answer(germany, "Berlin".to_owned(), None);
+
+        testing_env!(context.predecessor_account_id(carol.clone()).build());
+        contract.submit_answer(france, "Berlin".to_owned(), None);
+
+        testing_env!(context.predecessor_account_id(account_id).build());
+        empty_escrow(&mut contract, france);
+        assert_eq!(contract.delete_quiz(france), 0);
+        assert_eq!(contract.cleanup_deleted_quiz(france, 10), 0);
+
+        assert!(contract.retries_left.get(&alice).unwrap().get(&france).is_none());
+        assert!(contract.retries_left.get(&carol).unwrap().get(&france).is_none());
+        assert!(!contract.solved_quizzes.get(&alice).unwrap().contains(&france));
+        assert!(contract.quiz_participants.get(&france).is_none());
+
+        assert!(contract.solved_quizzes.get(&alice).unwrap().contains(&germany));
+        assert!(contract.retries_left.get(&alice).unwrap().get(&germany).is_some());
+    }
+
+    #[test]
+    fn update_quiz_before_attempts() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capitol of Germany".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        contract.update_quiz(quiz_id, Some("What is the capital of Germany".to_owned()), Some(vec!["dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2".to_owned()]), Some(U128(200)));
+
+        let json_quiz = &contract.get_published_quizzes(0, 10).quizzes[0];
+        assert_eq!(json_quiz.question, "What is the capital of Germany");
+        assert_eq!(json_quiz.prize_amount, U128(200));
+        assert_eq!(contract.question_index.get(&question_key("What is the capital of Germany")), Some(quiz_id));
+        assert_eq!(contract.question_index.get(&question_key("What is the capitol of Germany")), None);
+        assert!(contract.submit_answer(quiz_id, "Berlin".to_owned(), None).correct);
+    }
+
+    #[test]
+    fn update_quiz_needs_a_top_up_to_raise_the_prize() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.attached_deposit(100).build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        testing_env!(context.attached_deposit(0).build());
+        assert_eq!(
+            contract_error(|| { contract.update_quiz(quiz_id, None, None, Some(U128(250))); }),
+            QuizError::InsufficientDeposit { purpose: DepositPurpose::Escrow, required: U128(150), attached: U128(0) }
+        );
+
+        testing_env!(context.attached_deposit(150).build());
+        contract.update_quiz(quiz_id, None, None, Some(U128(250)));
+        assert_eq!(contract.get_quiz_funding(quiz_id).unwrap().balance, U128(250));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only free-text quizzes take new correct_hashes")]
+    fn update_quiz_rejects_hashes_for_other_kinds() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { kind: QuizKind::Numeric { target: 250, tolerance: 10 }, ..Default::default() };
+        let quiz_id = contract.create_quiz("How many validators does NEAR have".to_owned(), vec![], Some(U128(100)), None, true, Some(options));
+
+        contract.update_quiz(quiz_id, None, Some(vec![util::hash_answer("250")]), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only free-text quizzes take new correct_hashes")]
+    fn correct_answer_rejects_other_kinds() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { kind: QuizKind::Numeric { target: 250, tolerance: 10 }, ..Default::default() };
+        let quiz_id = contract.create_quiz("How many validators does NEAR have".to_owned(), vec![], Some(U128(100)), None, true, Some(options));
+
+        contract.correct_answer(quiz_id, vec![util::hash_answer("250")]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Quiz can no longer be edited once it has been attempted")]
+    fn update_quiz_after_reopen() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+        contract.reopen_quiz(quiz_id);
+        contract.update_quiz(quiz_id, Some("What is the capital of Germany".to_owned()), None, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Quiz can no longer be edited once it has been attempted")]
+    fn update_quiz_after_attempt() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+        contract.update_quiz(quiz_id, None, None, Some(U128(200)));
+    }
+
+    #[test]
+    fn deadline_rejects_late_answers() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_timestamp(0).build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { expires_at: Some(60 * NANOS_PER_SEC), ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options));
+
+        testing_env!(context.block_timestamp(15 * NANOS_PER_SEC).build());
+        assert!(!contract.get_published_quizzes(0, 10).quizzes[0].expired);
+        assert_eq!(contract.get_quiz_page(quiz_id, None).unwrap().time_remaining_sec, Some(45));
+
+        testing_env!(context.block_timestamp(60 * NANOS_PER_SEC).build());
+        assert!(contract.get_published_quizzes(0, 10).quizzes[0].expired);
+        assert_eq!(
+            contract_error(|| { contract.submit_answer(quiz_id, "Paris".to_owned(), None); }),
+            QuizError::QuizExpired { quiz_id, expires_at: 60 * NANOS_PER_SEC }
+        );
+    }
+
+    #[test]
+    fn extend_deadline_before_it_passes() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_timestamp(0).build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { expires_at: Some(60 * NANOS_PER_SEC), ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options));
+
+        testing_env!(context.block_timestamp(30 * NANOS_PER_SEC).build());
+        contract.extend_deadline(quiz_id, 120 * NANOS_PER_SEC);
+
+        testing_env!(context.block_timestamp(90 * NANOS_PER_SEC).build());
+        assert!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).correct);
+    }
+
+    #[test]
+    #[should_panic(expected = "This quiz has already expired")]
+    fn extend_deadline_after_it_passes() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_timestamp(0).build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { expires_at: Some(60 * NANOS_PER_SEC), ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options));
+
+        testing_env!(context.block_timestamp(61 * NANOS_PER_SEC).build());
+        contract.extend_deadline(quiz_id, 120 * NANOS_PER_SEC);
+    }
+
+    #[test]
+    fn scheduled_quiz_goes_live_at_publish_at() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_timestamp(0).build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { publish_at: Some(60 * NANOS_PER_SEC), ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, false, Some(options));
+
+        testing_env!(context.block_timestamp(30 * NANOS_PER_SEC).build());
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes.len(), 0);
+        assert!(contract.get_quiz_page(quiz_id, None).is_none());
+        assert!(contract.get_quiz(quiz_id).is_none());
+        assert_eq!(
+            contract_error(|| { contract.submit_answer(quiz_id, "Paris".to_owned(), None); }),
+            QuizError::QuizNotPublished { quiz_id }
+        );
+
+        testing_env!(context.block_timestamp(60 * NANOS_PER_SEC).build());
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes[0].quiz_id, quiz_id);
+        assert_eq!(contract.get_quiz(quiz_id).unwrap().quiz_id, quiz_id);
+        assert!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).correct);
+    }
+
+    #[test]
+    fn publish_scheduled_quiz_early() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_timestamp(0).build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { publish_at: Some(60 * NANOS_PER_SEC), ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, false, Some(options));
+
+        testing_env!(context.block_timestamp(30 * NANOS_PER_SEC).build());
+        contract.publish_quiz(quiz_id);
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes.len(), 1);
+    }
+
+    #[test]
+    fn archived_quiz_is_hidden_and_closed() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        contract.archive_quiz(quiz_id);
+        contract.publish_quiz(quiz_id);
+
+        assert_eq!(contract.get_quiz_status(quiz_id), Some(QuizStatus::Archived));
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes.len(), 0);
+        assert_eq!(
+            contract_error(|| { contract.submit_answer(quiz_id, "Paris".to_owned(), None); }),
+            QuizError::QuizNotPublished { quiz_id }
+        );
+    }
+
+    fn quiz_input(question: &str, correct_hash: &str) -> QuizInput {
+        QuizInput {
+            question: question.to_owned(),
+            correct_hashes: vec![correct_hash.to_owned()],
+            max_prize_amount: Some(U128(100)),
+            max_prize_near: None,
+            publish: true,
+            options: None
+        }
+    }
+
+    #[test]
+    fn create_quizzes_in_batch() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_ids = contract.create_quizzes(vec![
+            quiz_input("What is the capital of France", "5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1"),
+            quiz_input("What is the capital of Germany", "dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2")
+        ]);
+
+        assert_eq!(quiz_ids, vec![0, 1]);
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes.len(), 2);
+        assert!(contract.submit_answer(0, "Paris".to_owned(), None).correct);
+    }
+
+    #[test]
+    fn create_quizzes_validates_before_inserting() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            contract.create_quizzes(vec![
+                quiz_input("What is the capital of France", "5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1"),
+                quiz_input("what is the capital of france ", "5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1")
+            ])
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes.len(), 0);
+        assert_eq!(contract.get_quiz_status(0), None);
+    }
+
+    #[test]
+    fn list_quizzes_by_category() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let geography = |tags: Vec<&str>| QuizOptions {
+            category: Some(" Geography".to_owned()),
+            tags: tags.into_iter().map(str::to_owned).collect(),
+            ..Default::default()
+        };
+        let france = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(geography(vec!["europe"])));
+        let germany = contract.create_quiz("What is the capital of Germany".to_owned(), vec!["dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2".to_owned()], Some(U128(100)), None, true, Some(geography(vec![])));
+        contract.create_quiz("I am a city".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        let quizzes = contract.get_quizzes_by_category("geography".to_owned(), 0, 10);
+        assert_eq!(quizzes.iter().map(|quiz| quiz.quiz_id).collect::<Vec<_>>(), vec![france, germany]);
+        assert_eq!(quizzes[0].category.as_deref(), Some("geography"));
+        assert_eq!(quizzes[0].tags, vec!["europe".to_owned()]);
+        assert_eq!(contract.get_quizzes_by_category("Geography".to_owned(), 1, 10)[0].quiz_id, germany);
+
+        contract.unpublish_quiz(france);
+        empty_escrow(&mut contract, germany);
+        contract.delete_quiz(germany);
+        assert!(contract.get_quizzes_by_category("geography".to_owned(), 0, 10).is_empty());
+        assert!(contract.category_names.is_empty());
+    }
+
+    #[test]
+    fn hard_quiz_pays_difficulty_multiplier() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        contract.set_difficulty_multipliers(DifficultyMultipliers { easy_bps: 5_000, medium_bps: 10_000, hard_bps: 15_000 });
+        let options = QuizOptions { difficulty: Difficulty::Hard, ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options));
+
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes[0].difficulty, Difficulty::Hard);
+        assert_eq!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).amount, Some(U128(150)));
+
+        let record = &contract.get_payout_records(0, 1)[0];
+        assert_eq!(record.modifiers_applied, vec![("difficulty_multiplier".to_owned(), 5_000)]);
+    }
+
+    #[test]
+    fn correct_answer_resets_exhausted_retries() {
+        let owner = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let context = get_context(owner.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(owner.clone());
+        let quiz_id = contract.create_quiz("What is the capital of Germany".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        testing_env!(get_context(player.clone(), false).build());
+        for _ in 0..DEFAULT_RETRIES {
+            contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+        }
+        assert_eq!(contract.get_quiz_page(quiz_id, Some(player.clone())).unwrap().retries_left, Some(0));
+
+        testing_env!(context.build());
+        let reset = contract.correct_answer(quiz_id, vec!["dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2".to_owned()]);
+        assert_eq!(reset, vec![player.clone()]);
+        assert!(near_sdk::test_utils::get_logs()[0].contains("\"event\":\"quiz_answer_corrected\""));
+        assert_eq!(contract.quizzes.get(&quiz_id).unwrap().previous_hashes, vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()]);
+
+        testing_env!(get_context(player, false).build());
+        assert!(contract.submit_answer(quiz_id, "Berlin".to_owned(), None).correct);
+    }
+
+    #[test]
+    fn clone_quiz_copies_settings() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { placements: vec![U128(300), U128(200)], ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options));
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+        contract.archive_quiz(quiz_id);
+
+        let clone_id = contract.clone_quiz(quiz_id, true);
+        assert_ne!(clone_id, quiz_id);
+
+        let published = &contract.get_published_quizzes(0, 10).quizzes[0];
+        assert_eq!(published.quiz_id, clone_id);
+        assert_eq!(published.question, "What is the capital of France");
+        assert_eq!(published.remaining_placements, Some(vec![U128(300), U128(200)]));
+        assert_eq!(contract.submit_answer(clone_id, "Paris".to_owned(), None).amount, Some(U128(300)));
+    }
+
+    #[test]
+    fn group_quizzes_into_series() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let france = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+        let germany = contract.create_quiz("What is the capital of Germany".to_owned(), vec!["dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2".to_owned()], Some(U128(100)), None, true, None);
+        let draft = contract.create_quiz("I am a city".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, false, None);
+
+        let series_id = contract.create_series("Capitals Week 1".to_owned());
+        contract.add_to_series(series_id, germany);
+        contract.add_to_series(series_id, france);
+        contract.add_to_series(series_id, draft);
+
+        assert_eq!(contract.get_series_list(0, 10), vec![JsonSeries { series_id, name: "Capitals Week 1".to_owned(), quiz_count: 3 }]);
+        let quiz_ids: Vec<QuizId> = contract.get_series_quizzes(series_id).iter().map(|quiz| quiz.quiz_id).collect();
+        assert_eq!(quiz_ids, vec![france, germany]);
+
+        contract.remove_from_series(series_id, france);
+        assert_eq!(contract.get_series_list(0, 10)[0].quiz_count, 2);
+    }
+
+    #[test]
+    fn only_first_winners_are_paid() {
+        let owner = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(owner.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(owner);
+        let options = QuizOptions { max_winners: Some(1), ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options));
+
+        testing_env!(get_context(AccountId::new_unchecked("alice.near".to_owned()), false).build());
+        let first = contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+        assert_eq!(first.amount, Some(U128(100)));
+        assert!(!first.prize_pool_exhausted);
+
+        testing_env!(get_context(AccountId::new_unchecked("carol.near".to_owned()), false).build());
+        let second = contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+        assert!(second.correct);
+        assert!(second.prize_pool_exhausted);
+        assert_eq!(second.amount, None);
+        assert_eq!(contract.get_payout_records(0, 10).len(), 1);
+    }
+
+    #[test]
+    fn pause_blocks_submissions_and_creation() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        contract.pause();
+        assert!(contract.is_paused());
+        assert_eq!(
+            contract_error(|| { contract.submit_answer(quiz_id, "Paris".to_owned(), None); }),
+            QuizError::ContractPaused
+        );
+        assert_eq!(
+            contract_error(|| { contract.create_quiz("What is the capital of Germany".to_owned(), vec!["dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2".to_owned()], Some(U128(100)), None, true, None); }),
+            QuizError::ContractPaused
+        );
+
+        contract.resume();
+        assert!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).correct);
+    }
+
+    #[test]
+    fn owner_lists_all_quizzes() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let france = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+        let draft = contract.create_quiz("What is the capital of Germany".to_owned(), vec!["dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2".to_owned()], Some(U128(100)), None, false, None);
+        let deleted = contract.create_quiz("I am a city".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, false, None);
+        empty_escrow(&mut contract, deleted);
+        contract.delete_quiz(deleted);
+
+        let quizzes = contract.get_all_quizzes(0, 10);
+        assert_eq!(quizzes.iter().map(|quiz| (quiz.quiz_id, quiz.status)).collect::<Vec<_>>(), vec![
+            (france, QuizStatus::Published),
+            (draft, QuizStatus::Unpublished)
+        ]);
+        assert_eq!(quizzes[1].question, "What is the capital of Germany");
+        assert_eq!(contract.get_all_quizzes(1, 1).len(), 1);
+    }
+
+    #[test]
+    fn get_all_quizzes_by_non_owner() {
+        let owner = AccountId::new_unchecked("bob.near".to_owned());
+
+        testing_env!(get_context(owner.clone(), false).build());
+        let mut contract = QuizContract::new(owner);
+        let draft = contract.create_quiz("What is the capital of Germany".to_owned(), vec!["dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2".to_owned()], Some(U128(100)), None, false, None);
+
+        testing_env!(get_context(AccountId::new_unchecked("alice.near".to_owned()), false).build());
+        assert_eq!(contract.get_all_quizzes(0, 10).iter().map(|quiz| quiz.quiz_id).collect::<Vec<_>>(), vec![draft]);
+    }
+
+    #[test]
+    fn metadata_is_returned_with_quiz() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let metadata = QuizMetadata {
+            description: Some("Warm-up round".to_owned()),
+            image: Some("bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_owned()),
+            reference: None
+        };
+        let options = QuizOptions { metadata: Some(metadata.clone()), ..Default::default() };
+        contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options));
+
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes[0].metadata, Some(metadata));
+    }
+
+    #[test]
+    fn closed_quiz_stops_submissions() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.attached_deposit(100).build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        contract.close_quiz(quiz_id);
+        let logs = near_sdk::test_utils::get_logs();
+        assert!(logs[0].contains(r#""event":"quiz_refunded""#));
+        assert!(logs[1].contains(r#""event":"quiz_closed""#));
+        assert!(logs[1].contains(r#""refunded":"100""#));
+        assert_eq!(contract.get_quiz_status(quiz_id), Some(QuizStatus::Closed));
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes.len(), 0);
+        assert_eq!(
+            contract_error(|| { contract.submit_answer(quiz_id, "Paris".to_owned(), None); }),
+            QuizError::QuizNotPublished { quiz_id }
+        );
+    }
+
+    #[test]
+    fn status_changes_are_recorded() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_timestamp(10).build());
+
+        let mut contract = QuizContract::new(account_id.clone());
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, false, None);
+
+        testing_env!(context.block_timestamp(20).build());
+        contract.publish_quiz(quiz_id);
+        contract.close_quiz(quiz_id);
+
+        let history = contract.get_quiz_history(quiz_id);
+        assert_eq!(history.iter().map(|change| change.transition).collect::<Vec<_>>(), vec![
+            QuizTransition::Created,
+            QuizTransition::Published,
+            QuizTransition::Closed
+        ]);
+        assert_eq!(history[0], StatusChange { transition: QuizTransition::Created, timestamp: 10, account_id });
+        assert_eq!(history[1].timestamp, 20);
+    }
+
+    #[test]
+    fn prerequisites_gate_submissions() {
+        let owner = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let context = get_context(owner.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(owner);
+        let france = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+        let options = QuizOptions { prerequisites: vec![france], ..Default::default() };
+        let germany = contract.create_quiz("What is the capital of Germany".to_owned(), vec!["dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2".to_owned()], Some(U128(100)), None, true, Some(options));
+
+        testing_env!(get_context(player.clone(), false).build());
+        assert_eq!(contract.get_quiz_page(germany, Some(player.clone())).unwrap().eligible, Some(false));
+        assert_eq!(
+            contract_error(|| { contract.submit_answer(germany, "Berlin".to_owned(), None); }),
+            QuizError::PrerequisitesNotMet { quiz_id: germany, missing: vec![france] }
+        );
+
+        contract.submit_answer(france, "Paris".to_owned(), None);
+        assert_eq!(contract.get_quiz_page(germany, Some(player)).unwrap().eligible, Some(true));
+        assert!(contract.submit_answer(germany, "Berlin".to_owned(), None).correct);
+    }
+
+    #[test]
+    #[should_panic(expected = "Prerequisite quiz 7 does not exist")]
+    fn prerequisite_must_exist() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { prerequisites: vec![7], ..Default::default() };
+        contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options));
+    }
+
+    #[test]
+    fn reopened_quiz_starts_a_new_round() {
+        let owner = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let context = get_context(owner.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(owner);
+        let options = QuizOptions { max_winners: Some(1), ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options));
+
+        testing_env!(get_context(player.clone(), false).build());
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+
+        testing_env!(context.build());
+        contract.close_quiz(quiz_id);
+        assert_eq!(contract.reopen_quiz(quiz_id), 1);
+        assert_eq!(contract.get_quiz_status(quiz_id), Some(QuizStatus::Published));
+
+        testing_env!(get_context(player.clone(), false).build());
+        assert_eq!(contract.get_quiz_page(quiz_id, Some(player.clone())).unwrap().solved, Some(false));
+        let result = contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+        assert_eq!(result.amount, Some(U128(100)));
+
+        let rounds: Vec<u32> = contract.get_payouts_for(player, 0, 10).iter().map(|record| record.round).collect();
+        assert_eq!(rounds, vec![0, 1]);
+    }
+
+    #[test]
+    fn normalized_quiz_ignores_case_and_spacing() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { normalize_answers: true, ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of the USA".to_owned(), vec![util::hash_answer("washington dc")], Some(U128(100)), None, true, Some(options));
+
+        assert!(contract.submit_answer(quiz_id, "  Washington   DC ".to_owned(), None).correct);
+    }
+
+    #[test]
+    fn multiple_choice_quiz() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions {
+            kind: QuizKind::MultipleChoice {
+                options: vec!["Lyon".to_owned(), "Paris".to_owned(), "Nice".to_owned()],
+                salt: "week-1".to_owned()
+            },
+            ..Default::default()
+        };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec![util::hash_answer(&util::choice_answer("week-1", 1))], Some(U128(100)), None, true, Some(options));
+
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes[0].options, Some(vec!["Lyon".to_owned(), "Paris".to_owned(), "Nice".to_owned()]));
+        assert!(!contract.submit_choice(quiz_id, 0, None).correct);
+        assert!(contract.submit_choice(quiz_id, 1, None).correct);
+    }
+
+    #[test]
+    #[should_panic(expected = "There are only 2 options")]
+    fn choice_out_of_range() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions {
+            kind: QuizKind::MultipleChoice { options: vec!["Lyon".to_owned(), "Paris".to_owned()], salt: "week-1".to_owned() },
+            ..Default::default()
+        };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec![util::hash_answer(&util::choice_answer("week-1", 1))], Some(U128(100)), None, true, Some(options));
+
+        contract.submit_choice(quiz_id, 2, None);
+    }
+
+    #[test]
+    fn any_listed_answer_is_accepted() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let correct_hashes = vec![util::hash_answer("USA"), util::hash_answer("United States")];
+        let quiz_id = contract.create_quiz("Which country has the most Nobel laureates".to_owned(), correct_hashes, Some(U128(100)), None, true, None);
+
+        assert!(!contract.submit_answer(quiz_id, "France".to_owned(), None).correct);
+        assert!(contract.submit_answer(quiz_id, "United States".to_owned(), None).correct);
+    }
+
+    #[test]
+    fn committed_answer_pays_out_on_reveal() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { commit_reveal: true, ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec![util::hash_answer("Paris")], Some(U128(100)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(player.clone()).block_index(10).build());
+        assert_eq!(contract_error(|| { contract.submit_answer(quiz_id, "Paris".to_owned(), None); }), QuizError::CommitRequired { quiz_id });
+        assert_eq!(contract.commit_answer(quiz_id, util::answer_commitment(&player, quiz_id, "Paris", "pepper")), 12);
+
+        testing_env!(context.block_index(11).build());
+        assert_eq!(
+            contract_error(|| { contract.reveal_answer(quiz_id, "Paris".to_owned(), "pepper".to_owned(), None); }),
+            QuizError::RevealTooEarly { quiz_id, reveal_after: 12 }
+        );
+
+        testing_env!(context.block_index(12).build());
+        assert_eq!(
+            contract_error(|| { contract.reveal_answer(quiz_id, "Paris".to_owned(), "salt".to_owned(), None); }),
+            QuizError::CommitmentMismatch { quiz_id }
+        );
+        let result = contract.reveal_answer(quiz_id, "Paris".to_owned(), "pepper".to_owned(), None);
+        assert_eq!(result.amount, Some(U128(100)));
+        assert_eq!(
+            contract_error(|| { contract.reveal_answer(quiz_id, "Paris".to_owned(), "pepper".to_owned(), None); }),
+            QuizError::NoCommitment { quiz_id }
+        );
+    }
+
+    #[test]
+    fn copied_commitment_cannot_be_revealed() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+        let copier = AccountId::new_unchecked("carol.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { commit_reveal: true, ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec![util::hash_answer("Paris")], Some(U128(100)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(copier).block_index(10).build());
+        contract.commit_answer(quiz_id, util::answer_commitment(&player, quiz_id, "Paris", "pepper"));
+
+        testing_env!(context.block_index(12).build());
+        assert_eq!(
+            contract_error(|| { contract.reveal_answer(quiz_id, "Paris".to_owned(), "pepper".to_owned(), None); }),
+            QuizError::CommitmentMismatch { quiz_id }
+        );
+    }
+
+    #[test]
+    fn salted_quiz_hashes_salt_before_answer() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { answer_salt: "f1c3".to_owned(), ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec![util::salted_hash(HashAlgo::Sha256, "f1c3", "Paris")], Some(U128(100)), None, true, Some(options));
+
+        assert_eq!(util::salted_hash(HashAlgo::Sha256, "", "Paris"), util::hash_answer("Paris"));
+        assert!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).correct);
+    }
+
+    #[test]
+    fn one_shot_quiz_allows_a_single_attempt() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { max_retries: Some(1), ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("alice.near".to_owned())).build());
+        assert_eq!(contract.submit_answer(quiz_id, "Berlin".to_owned(), None).retries_left, 0);
+        assert_eq!(contract_error(|| { contract.submit_answer(quiz_id, "Paris".to_owned(), None); }), QuizError::OutOfRetries { quiz_id, refresh_at: None });
+    }
+
+    #[test]
+    fn default_retries_apply_to_new_quizzes() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        contract.set_default_retries(5);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("alice.near".to_owned())).build());
+        assert_eq!(contract.submit_answer(quiz_id, "Berlin".to_owned(), None).retries_left, 4);
+        assert_eq!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).amount, Some(U128(50)));
+    }
+
+    #[test]
+    fn bought_retry_allows_another_attempt() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { max_retries: Some(1), retry_price: Some(U128(10)), ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("alice.near".to_owned())).build());
+        contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+
+        testing_env!(context.attached_deposit(5).build());
+        assert_eq!(
+            contract_error(|| contract.buy_retry(quiz_id)),
+            QuizError::InsufficientDeposit { purpose: DepositPurpose::ExtraRetry, required: U128(10), attached: U128(5) }
+        );
+
+        testing_env!(context.attached_deposit(10).build());
+        contract.buy_retry(quiz_id);
+        assert_eq!(contract.get_withdrawable_balance(), U128(10));
+
+        testing_env!(context.attached_deposit(0).build());
+        assert!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).correct);
+    }
+
+    #[test]
+    fn hint_reduces_the_payout() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { hint: Some("It is on the Seine".to_owned()), hint_penalty_bps: 2_500, ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(player.clone()).build());
+        assert_eq!(contract.request_hint(quiz_id), "It is on the Seine");
+        assert_eq!(contract.submit_answer(quiz_id, "Berlin".to_owned(), None).retries_left, 2);
+        assert_eq!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).amount, Some(U128(37)));
+
+        let record = contract.get_payouts_for(player, 0, 1).pop().unwrap();
+        assert_eq!(record.modifiers_applied, vec![("retry_decay".to_owned(), -5_000), ("hint_penalty".to_owned(), -2_500)]);
+    }
+
+    #[test]
+    fn answer_after_attempt_window_counts_as_miss() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { attempt_window_sec: Some(30), ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("alice.near".to_owned())).build());
+        assert_eq!(contract_error(|| { contract.submit_answer(quiz_id, "Paris".to_owned(), None); }), QuizError::AttemptNotStarted { quiz_id });
+
+        assert_eq!(contract.start_attempt(quiz_id), 30 * NANOS_PER_SEC);
+        testing_env!(context.block_timestamp(31 * NANOS_PER_SEC).build());
+        let result = contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+        assert!(!result.correct);
+        assert_eq!(result.retries_left, 2);
+
+        contract.start_attempt(quiz_id);
+        testing_env!(context.block_timestamp(60 * NANOS_PER_SEC).build());
+        assert!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).correct);
+    }
+
+    #[test]
+    fn failed_attempt_starts_cooldown() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { cooldown_sec: 60, ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("alice.near".to_owned())).block_timestamp(10 * NANOS_PER_SEC).build());
+        contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+
+        testing_env!(context.block_timestamp(69 * NANOS_PER_SEC).build());
+        assert_eq!(
+            contract_error(|| { contract.submit_answer(quiz_id, "Paris".to_owned(), None); }),
+            QuizError::AttemptCooldown { quiz_id, retry_at: 70 * NANOS_PER_SEC }
+        );
+
+        testing_env!(context.block_timestamp(70 * NANOS_PER_SEC).build());
+        assert!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).correct);
+    }
+
+    #[test]
+    fn partly_correct_parts_pay_a_share() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let part_hashes = vec![util::hash_answer("Paris"), util::hash_answer("Berlin"), util::hash_answer("Rome"), util::hash_answer("Madrid")];
+        let options = QuizOptions { kind: QuizKind::MultiPart { part_hashes }, ..Default::default() };
+        let quiz_id = contract.create_quiz("Name the capitals of France, Germany, Italy and Spain".to_owned(), vec![], Some(U128(100)), None, true, Some(options));
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes[0].answer_parts, 4);
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("alice.near".to_owned())).build());
+        let answers = |parts: [&str; 4]| parts.iter().map(|part| part.to_string()).collect::<Vec<_>>();
+        assert_eq!(contract.submit_answers(quiz_id, answers(["Lyon", "Bonn", "Milan", "Seville"]), None).retries_left, 2);
+
+        let result = contract.submit_answers(quiz_id, answers(["Paris", "Berlin", "Milan", "Madrid"]), None);
+        assert!(result.correct);
+        assert_eq!(result.amount, Some(U128(37)));
+        assert_eq!(result.message, "3 of 4 parts are correct. You've got 37 yoctoNEAR");
+    }
+
+    #[test]
+    fn numeric_answer_within_tolerance_is_correct() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { kind: QuizKind::Numeric { target: 250, tolerance: 10 }, ..Default::default() };
+        let quiz_id = contract.create_quiz("How many validators does NEAR have".to_owned(), vec![], Some(U128(100)), None, true, Some(options));
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes[0].numeric_tolerance, Some(10));
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("alice.near".to_owned())).build());
+        assert!(!contract.submit_numeric_answer(quiz_id, 239, None).correct);
+        assert!(!contract.submit_answer(quiz_id, "about 250".to_owned(), None).correct);
+        assert!(contract.submit_numeric_answer(quiz_id, 260, None).correct);
+    }
+
+    #[test]
+    fn implicit_account_signs_without_registering() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player: String = signing_key().1.as_bytes().iter().map(|byte| format!("{:02x}", byte)).collect();
+        let player = AccountId::new_unchecked(player);
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("relayer.near".to_owned())).build());
+        assert!(contract.submit_answer_signed(quiz_id, "Paris".to_owned(), player.clone(), 1, sign_answer(quiz_id, "Paris", 1)).correct);
+        assert!(contract.solved_quizzes.get(&player).unwrap().contains(&quiz_id));
+    }
+
+    #[test]
+    fn attempts_are_recorded_with_timestamps() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(player.clone()).block_timestamp(5).build());
+        contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+        testing_env!(context.block_timestamp(9).build());
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+
+        assert_eq!(contract.get_attempt_history(player.clone(), quiz_id, 0, 10), vec![
+            AttemptRecord { round: 0, correct: false, timestamp: 5 },
+            AttemptRecord { round: 0, correct: true, timestamp: 9 }
+        ]);
+        assert_eq!(contract.get_attempt_history(player.clone(), quiz_id, 1, 10).len(), 1);
+        assert!(contract.get_attempt_history(player, quiz_id + 1, 0, 10).is_empty());
+    }
+
+    #[test]
+    fn sequence_pays_after_the_last_step() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let steps = vec![
+            SequenceStep { question: "What is the capital of France".to_owned(), correct_hashes: vec![util::hash_answer("Paris")] },
+            SequenceStep { question: "What is the capital of Germany".to_owned(), correct_hashes: vec![util::hash_answer("Berlin")] }
+        ];
+        let options = QuizOptions { kind: QuizKind::Sequence { steps }, ..Default::default() };
+        let quiz_id = contract.create_quiz("European capitals exam".to_owned(), vec![], Some(U128(100)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(player.clone()).build());
+        assert_eq!(contract.get_current_step(quiz_id, player.clone()).unwrap().question, "What is the capital of France");
+
+        let result = contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+        assert!(result.correct);
+        assert_eq!(result.amount, None);
+        assert_eq!(contract.get_current_step(quiz_id, player.clone()), Some(JsonStep { step: 1, steps: 2, question: "What is the capital of Germany".to_owned() }));
+
+        assert!(!contract.submit_answer(quiz_id, "Paris".to_owned(), None).correct);
+        assert_eq!(contract.submit_answer(quiz_id, "Berlin".to_owned(), None).amount, Some(U128(50)));
+        assert!(contract.solved_quizzes.get(&player).unwrap().contains(&quiz_id));
+    }
+
+    #[test]
+    fn reviewed_submission_pays_when_accepted() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+        let grader = AccountId::new_unchecked("grader.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        contract.add_grader(grader.clone());
+        let options = QuizOptions { kind: QuizKind::ManualReview, ..Default::default() };
+        let quiz_id = contract.create_quiz("Describe the Paris commune in one sentence".to_owned(), vec![], Some(U128(100)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(player.clone()).build());
+        let result = contract.submit_answer(quiz_id, "A short-lived government".to_owned(), None);
+        assert_eq!(result.message, "Your answer is waiting for review");
+        assert_eq!(contract.get_review_queue(quiz_id, 0, 10)[0].account_id, player);
+
+        assert_eq!(contract_error(|| { contract.grade_submission(quiz_id, player.clone(), true, None); }), QuizError::NotGrader { account_id: player.clone() });
+
+        testing_env!(context.predecessor_account_id(grader).build());
+        let result = contract.grade_submission(quiz_id, player.clone(), false, Some("Too vague".to_owned()));
+        assert_eq!(result.retries_left, 2);
+        assert_eq!(contract.get_submission(player.clone(), quiz_id).unwrap().status, ReviewStatus::Rejected { reason: Some("Too vague".to_owned()) });
+        assert!(contract.get_review_queue(quiz_id, 0, 10).is_empty());
+
+        testing_env!(context.predecessor_account_id(player.clone()).build());
+        contract.submit_answer(quiz_id, "A revolutionary government that ruled Paris in 1871".to_owned(), None);
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("grader.near".to_owned())).build());
+        assert_eq!(contract.grade_submission(quiz_id, player.clone(), true, None).amount, Some(U128(50)));
+        assert!(contract.solved_quizzes.get(&player).unwrap().contains(&quiz_id));
+    }
+
+    #[test]
+    fn batch_reports_errors_per_item() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let paris = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+        let berlin = contract.create_quiz("What is the capital of Germany".to_owned(), vec!["dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2".to_owned()], Some(U128(100)), None, true, None);
+        let paid = contract.create_quiz("What is the capital of Italy".to_owned(), vec![util::hash_answer("Rome")], Some(U128(100)), None, true, Some(QuizOptions {
+            fee_schedule: vec![U128(10), U128(10), U128(10)],
+            ..Default::default()
+        }));
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("alice.near".to_owned())).build());
+        let results = contract.submit_answer_batch(vec![
+            (paris, "Paris".to_owned()),
+            (berlin, "Munich".to_owned()),
+            (paid, "Rome".to_owned()),
+            (paris, "Paris".to_owned()),
+            (99, "Oslo".to_owned())
+        ]);
+
+        assert!(results[0].result.as_ref().unwrap().correct);
+        assert_eq!(results[1].result.as_ref().unwrap().retries_left, 2);
+        assert_eq!(results[2].error, Some(QuizError::InsufficientDeposit { purpose: DepositPurpose::Attempt, required: U128(10), attached: U128(0) }));
+        assert_eq!(results[3].error, Some(QuizError::AlreadySolved { quiz_id: paris }));
+        assert_eq!(results[4].error, Some(QuizError::QuizNotFound { quiz_id: 99 }));
+    }
+
+    #[test]
+    fn batch_rejects_multi_part_items_without_reverting_the_rest() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let paris = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+        let part_hashes = vec![util::hash_answer("Berlin"), util::hash_answer("Rome")];
+        let options = QuizOptions { kind: QuizKind::MultiPart { part_hashes }, ..Default::default() };
+        let capitals = contract.create_quiz("Name the capitals of Germany and Italy".to_owned(), vec![], Some(U128(100)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("alice.near".to_owned())).build());
+        let results = contract.submit_answer_batch(vec![
+            (capitals, "Berlin".to_owned()),
+            (paris, "Paris".to_owned())
+        ]);
+
+        assert_eq!(results[0].error, Some(QuizError::WrongAnswerCount { quiz_id: capitals, expected: 2 }));
+        assert_eq!(results[1].result.as_ref().unwrap().amount, Some(U128(100)));
+        assert_eq!(contract.get_retries_left(AccountId::new_unchecked("alice.near".to_owned()), capitals), Some(DEFAULT_RETRIES));
+        assert_eq!(contract_error(|| {
+            contract.submit_answer(capitals, "Berlin".to_owned(), None);
+        }), QuizError::WrongAnswerCount { quiz_id: capitals, expected: 2 });
+    }
+
+    #[test]
+    fn accepts_imported_keccak_hashes() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { hash_algo: HashAlgo::Keccak256, ..Default::default() };
+        let correct_hash = "90a894675cfa858465214570edc881aeb6a199b8fc3e79cb33a5099da2d7cd44".to_owned();
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec![correct_hash.clone()], Some(U128(100)), None, true, Some(options));
+        assert_eq!(util::hash_with(HashAlgo::Keccak256, "Paris"), correct_hash);
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("alice.near".to_owned())).build());
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes[0].hash_algo, HashAlgo::Keccak256);
+        assert!(!contract.submit_answer(quiz_id, "5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned(), None).correct);
+        assert!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).correct);
+    }
+
+    #[test]
+    fn first_solver_gets_bonus() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let alice = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { first_solver_bonus: Some(U128(50)), ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options));
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes[0].first_solver_bonus, Some(U128(50)));
+
+        testing_env!(context.predecessor_account_id(alice.clone()).build());
+        assert_eq!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).amount, Some(U128(150)));
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("carol.near".to_owned())).build());
+        assert_eq!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).amount, Some(U128(100)));
+        assert_eq!(contract.get_first_solver(quiz_id), Some(alice));
+    }
+
+    #[test]
+    fn quiz_creation_requires_escrow() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.attached_deposit(199).build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { max_winners: Some(2), ..Default::default() };
+        assert_eq!(
+            contract_error(|| { contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options)); }),
+            QuizError::InsufficientDeposit { purpose: DepositPurpose::Escrow, required: U128(200), attached: U128(199) }
+        );
+    }
+
+    #[test]
+    fn payouts_draw_down_the_escrow() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.attached_deposit(150).build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("alice.near".to_owned())).attached_deposit(0).build());
+        assert_eq!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).amount, Some(U128(100)));
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("carol.near".to_owned())).build());
+        assert_eq!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).amount, Some(U128(50)));
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("dave.near".to_owned())).build());
+        assert!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).prize_pool_exhausted);
+    }
+
+    #[test]
+    fn batch_creation_refunds_surplus_escrow() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.attached_deposit(250).build());
+
+        let mut contract = QuizContract::new(account_id.clone());
+        contract.create_quizzes(vec![
+            QuizInput { question: "What is the capital of France".to_owned(), correct_hashes: vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], max_prize_amount: Some(U128(100)), max_prize_near: None, publish: true, options: None },
+            QuizInput { question: "What is the capital of Germany".to_owned(), correct_hashes: vec!["dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2".to_owned()], max_prize_amount: Some(U128(100)), max_prize_near: None, publish: true, options: None }
+        ]);
+
+        let receipts = transfer_receipts();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].receiver_id, account_id);
+        assert_eq!(receipts[0].actions, vec![near_sdk::mock::VmAction::Transfer { deposit: 50 }]);
+        assert_eq!(contract.quizzes.get(&1).unwrap().escrow, 100);
+    }
+
+    #[test]
+    fn sponsors_top_up_the_prize_pool() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let sponsor = AccountId::new_unchecked("sponsor.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.attached_deposit(100).build());
+
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(sponsor.clone()).attached_deposit(60).build());
+        assert_eq!(contract.fund_quiz(quiz_id), U128(160));
+        contract.fund_quiz(quiz_id);
+        assert_eq!(contract.get_quiz_funding(quiz_id), Some(QuizFunding { balance: U128(220), total_funded: U128(220), funders: 2 }));
+        assert_eq!(contract.quiz_funders.get(&quiz_id).unwrap().get(&sponsor), Some(120));
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("alice.near".to_owned())).attached_deposit(0).build());
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+        assert_eq!(contract.get_quiz_funding(quiz_id).unwrap().balance, U128(120));
+        assert_eq!(
+            contract_error(|| { contract.fund_quiz(quiz_id); }),
+            QuizError::InsufficientDeposit { purpose: DepositPurpose::Escrow, required: U128(1), attached: U128(0) }
+        );
+    }
+
+    #[test]
+    fn funders_split_leftover_escrow() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let sponsor = AccountId::new_unchecked("sponsor.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.attached_deposit(300).build());
+
+        let mut contract = QuizContract::new(account_id.clone());
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(sponsor.clone()).attached_deposit(100).build());
+        contract.fund_quiz(quiz_id);
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("alice.near".to_owned())).attached_deposit(0).build());
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+
+        testing_env!(context.predecessor_account_id(sponsor.clone()).build());
+        assert_eq!(contract_error(|| { contract.refund_quiz(quiz_id); }), QuizError::EscrowLocked { quiz_id });
+
+        testing_env!(context.predecessor_account_id(account_id.clone()).build());
+        contract.close_quiz(quiz_id);
+        let refunds: Vec<_> = transfer_receipts().into_iter().map(|receipt| (receipt.receiver_id, receipt.actions)).collect();
+        assert_eq!(refunds, vec![
+            (account_id, vec![near_sdk::mock::VmAction::Transfer { deposit: 225 }]),
+            (sponsor.clone(), vec![near_sdk::mock::VmAction::Transfer { deposit: 75 }])
+        ]);
+        assert!(near_sdk::test_utils::get_logs().last().unwrap().contains(r#""refunded":"300""#));
+
+        testing_env!(context.predecessor_account_id(sponsor).build());
+        assert_eq!(contract_error(|| { contract.refund_quiz(quiz_id); }), QuizError::NothingToRefund);
+        assert_eq!(contract.get_quiz_funding(quiz_id), Some(QuizFunding { balance: U128(0), total_funded: U128(0), funders: 0 }));
+    }
+
+    #[test]
+    fn token_prize_is_funded_and_paid_in_the_token() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let token = AccountId::new_unchecked("usdc.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.attached_deposit(0).build());
+
+        let mut contract = QuizContract::new(account_id.clone());
+        let options = QuizOptions { prize_token: Some(token.clone()), ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("dai.near".to_owned())).build());
+        assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| contract.ft_on_transfer(account_id.clone(), U128(100), quiz_id.to_string()))).is_err());
+
+        testing_env!(context.predecessor_account_id(token.clone()).build());
+        contract.ft_on_transfer(account_id, U128(100), quiz_id.to_string());
+        assert_eq!(contract.get_quiz_funding(quiz_id).unwrap().balance, U128(100));
+
+        testing_env!(context.predecessor_account_id(player.clone()).build());
+        assert_eq!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).amount, Some(U128(100)));
+        let receipts = near_sdk::test_utils::get_created_receipts();
+        assert_eq!(receipts[0].receiver_id, token);
+        assert!(matches!(&receipts[0].actions[0], near_sdk::mock::VmAction::FunctionCall { function_name, deposit: 1, .. } if function_name == "ft_transfer"));
+        assert!(contract.claimable_prizes.get(&player).is_none());
+    }
+
+    #[test]
+    fn failed_token_transfer_becomes_claimable() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let token = AccountId::new_unchecked("usdc.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build(), near_sdk::VMConfig::test(), near_sdk::RuntimeFeesConfig::test(), Default::default(), vec![PromiseResult::Failed]);
+
+        let mut contract = QuizContract::new(account_id);
+        contract.on_ft_transfer(token.clone(), player.clone(), U128(40));
+        contract.on_ft_transfer(token.clone(), player.clone(), U128(60));
+        assert_eq!(contract.get_claimable_tokens(player.clone(), token.clone()), U128(100));
+
+        testing_env!(context.clone().predecessor_account_id(player.clone()).attached_deposit(0).build());
+        assert_eq!(contract.claim_tokens(token.clone()), U128(100));
+        assert_eq!(contract.get_claimable_tokens(player, token), U128(0));
+    }
+
+    #[test]
+    fn nft_prize_goes_to_the_first_solver() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let nft_contract = AccountId::new_unchecked("nft.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.attached_deposit(0).build());
+
+        let mut contract = QuizContract::new(account_id.clone());
+        let token = NftToken { contract_id: nft_contract.clone(), token_id: "trophy-1".to_owned() };
+        let options = QuizOptions { nft_prize: Some(token.clone()), ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(0)), None, true, Some(options));
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes[0].nft_prize, Some(token.clone()));
+
+        testing_env!(context.predecessor_account_id(nft_contract.clone()).build());
+        let kept = contract.nft_on_transfer(account_id.clone(), account_id.clone(), "trophy-1".to_owned(), quiz_id.to_string());
+        assert!(matches!(kept, PromiseOrValue::Value(false)));
+
+        testing_env!(context.predecessor_account_id(player.clone()).build());
+        assert_eq!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).message, "Your answer is correct. You won the NFT prize");
+        let receipts = near_sdk::test_utils::get_created_receipts();
+        assert_eq!(receipts[0].receiver_id, nft_contract);
+        assert!(matches!(&receipts[0].actions[0], near_sdk::mock::VmAction::FunctionCall { function_name, deposit: 1, .. } if function_name == "nft_transfer"));
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("carol.near".to_owned())).build());
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+        assert_eq!(
+            contract.get_nft_prize(quiz_id),
+            Some(NftPrize { token, deposited_by: Some(account_id), winner: Some(player), delivered: false })
+        );
+    }
+
+    #[test]
+    fn split_prize_is_shared_at_close() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let alice = AccountId::new_unchecked("alice.near".to_owned());
+        let carol = AccountId::new_unchecked("carol.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.attached_deposit(900).build());
 
-#[derive(Serialize)]
-#[serde(crate = "near_sdk::serde")]
-pub struct PublishedQuizzes {
-    quizzes: Vec<JsonQuiz>,
-}
+        let mut contract = QuizContract::new(account_id.clone());
+        let options = QuizOptions { prize_split: Some(PrizeSplit::ByAttempts), max_winners: Some(3), ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(900)), None, true, Some(options));
 
-#[derive(Serialize, Deserialize)]
-#[serde(crate = "near_sdk::serde")]
-pub struct JsonQuiz {
-    quiz_id: QuizId,
-    question: String,
-    prize_amount: String
-}
+        testing_env!(context.predecessor_account_id(alice.clone()).attached_deposit(0).build());
+        assert_eq!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).amount, Some(U128(0)));
 
-#[derive(BorshDeserialize, BorshSerialize, Debug)]
-pub struct Quiz {
-    status: QuizStatus,
-    question: String,
-    correct_hash: String,
-    max_prize_amount: u128
-}
+        testing_env!(context.predecessor_account_id(carol.clone()).build());
+        contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+        assert_eq!(contract_error(|| { contract.distribute_split(quiz_id); }), QuizError::SplitPending { quiz_id });
 
-#[near_bindgen]
-#[derive(BorshSerialize, BorshDeserialize, PanicOnDefault)]
-pub struct QuizContract {
-    owner_id: AccountId,
-    quizzes: LookupMap<QuizId, Quiz>,
-    published_quiz_ids: UnorderedSet<QuizId>,
-    solved_quizzes: LookupMap<AccountId, UnorderedSet<QuizId>>,
-    retries_left: LookupMap<AccountId, LookupMap<QuizId, usize>>,
-    current_quiz_id: QuizId
-}
+        testing_env!(context.predecessor_account_id(account_id.clone()).build());
+        contract.close_quiz(quiz_id);
+        assert_eq!(contract.get_split_winners(quiz_id).len(), 2);
+        assert_eq!(contract.distribute_split(quiz_id), U128(899));
 
-#[near_bindgen]
-impl QuizContract {
-    #[init]
-    pub fn new(owner_id: AccountId) -> Self {
-        Self {
-            owner_id: owner_id,
-            quizzes: LookupMap::new(StorageKey::Quizzes),
-            published_quiz_ids: UnorderedSet::new(StorageKey::PublishedQuizzes),
-            solved_quizzes: LookupMap::new(StorageKey::SolvedQuizzes),
-            retries_left: LookupMap::new(StorageKey::RetriesLeft),
-            current_quiz_id: 0
-        }
+        let records = contract.get_payout_records(0, 2);
+        assert_eq!((records[0].account_id.clone(), records[0].final_amount), (alice, U128(514)));
+        assert_eq!((records[1].account_id.clone(), records[1].final_amount), (carol, U128(385)));
+        assert!(contract.get_split_winners(quiz_id).is_empty());
+        assert_eq!(contract_error(|| { contract.distribute_split(quiz_id); }), QuizError::NothingToClaim);
     }
 
-    pub fn submit_answer(&mut self, quiz_id: QuizId, answer: String) -> String {
-        let quiz = self.quizzes.get(&quiz_id).expect("No such quiz found");
-        assert!(quiz.status == QuizStatus::Published, "Cannot submit an answer to unpublished quiz");
-        let account_id = env::predecessor_account_id();
-        let mut solved_quizzes_set = self.solved_quizzes.get(&account_id).unwrap_or_else(|| {
-            let mut prefix = Vec::with_capacity(33);
-            prefix.push(b's');
-            prefix.extend(env::sha256(account_id.as_bytes()));
-            UnorderedSet::new(prefix)
-        });
+    #[test]
+    fn time_decay_pays_early_solvers_more() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
 
-        if solved_quizzes_set.contains(&quiz_id) {
-            env::panic_str("This quiz is already solved by you");
-        }
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_timestamp(0).build());
 
-        let mut retries_left_map: LookupMap<QuizId, usize> = self.retries_left.get(&account_id).unwrap_or_else(|| {
-            let mut prefix = Vec::with_capacity(33);
-            prefix.push(b'r');
-            prefix.extend(env::sha256(account_id.as_bytes()));
-            LookupMap::new(prefix)
-        });
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions {
+            expires_at: Some(100 * NANOS_PER_SEC),
+            time_decay: Some(TimeDecay { floor_bps: 2_000, step_sec: None }),
+            ..Default::default()
+        };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1000)), None, true, Some(options));
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes[0].prize_amount, U128(1000));
 
-        let mut retries_left = retries_left_map.get(&quiz_id).unwrap_or(3);
+        testing_env!(context.predecessor_account_id(player).block_timestamp(50 * NANOS_PER_SEC).build());
+        assert_eq!(contract.get_quiz_page(quiz_id, None).unwrap().prize_amount, U128(600));
 
-        if retries_left == 0 {
-            env::panic_str("You can no longer solve this quiz. You are out of tries.");
-        }
+        let result = contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+        assert_eq!(result.amount, Some(U128(600)));
+        assert_eq!(contract.get_payout_records(0, 1)[0].modifiers_applied, vec![("time_decay".to_owned(), -4_000)]);
+    }
 
-        let answer_hash = format!("{:x}", Sha256::digest(answer.as_bytes()));
+    #[test]
+    fn streaks_raise_payouts_until_a_miss() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
 
-        if answer_hash == quiz.correct_hash {
-            solved_quizzes_set.insert(&quiz_id);
-            self.solved_quizzes.insert(&account_id, &solved_quizzes_set);
+        let mut contract = QuizContract::new(account_id.clone());
+        contract.set_streak_config(StreakConfig { bonus_bps_per_solve: 1_000, max_bonus_bps: 1_500 });
+        let quiz_ids: Vec<QuizId> = ["France", "Italy", "Spain", "Peru"].iter().map(|country| {
+            let options = QuizOptions { allow_duplicate: true, ..Default::default() };
+            contract.create_quiz(format!("What is the capital of {}", country), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1000)), None, true, Some(options))
+        }).collect();
 
-            let amount = quiz.max_prize_amount / (4 - retries_left) as u128;
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(0).build());
+        assert_eq!(contract.submit_answer(quiz_ids[0], "Paris".to_owned(), None).amount, Some(U128(1000)));
+        assert_eq!(contract.submit_answer(quiz_ids[1], "Paris".to_owned(), None).amount, Some(U128(1100)));
+        assert_eq!(contract.submit_answer(quiz_ids[2], "Paris".to_owned(), None).amount, Some(U128(1150)));
+        assert_eq!(contract.get_streak(player.clone()), Streak { current: 3, best: 3 });
 
-            Promise::new(account_id.clone()).transfer(amount);
+        contract.submit_answer(quiz_ids[3], "Berlin".to_owned(), None);
+        assert_eq!(contract.get_streak(player.clone()), Streak { current: 0, best: 3 });
+        assert_eq!(contract.submit_answer(quiz_ids[3], "Paris".to_owned(), None).amount, Some(U128(500)));
+    }
 
-            return format!("Your answer is correct. You've got {} yoctoNEAR", amount);
-        } else {
-            retries_left -= 1;
+    #[test]
+    fn expired_escrow_rolls_into_jackpot_quiz() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
 
-            retries_left_map.insert(&quiz_id, &retries_left);
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_timestamp(0).attached_deposit(1000).build());
 
-            self.retries_left.insert(&account_id, &retries_left_map);
+        let mut contract = QuizContract::new(account_id.clone());
+        let options = QuizOptions { expires_at: Some(60 * NANOS_PER_SEC), ..Default::default() };
+        let expiring = contract.create_quiz("What is the capital of Italy".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1000)), None, true, Some(options));
 
-            if retries_left == 0 {
-                return format!("The answer is not right, you are out of tries");
-            }
+        testing_env!(context.block_timestamp(90 * NANOS_PER_SEC).build());
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1000)), None, true, None);
+        assert_eq!(contract.roll_into_jackpot(expiring), U128(1000));
+        assert_eq!(contract.get_quiz_funding(expiring).unwrap().balance, U128(0));
+        assert_eq!(contract.attach_jackpot(quiz_id, None), U128(2000));
+        assert_eq!(contract.get_jackpot(), U128(0));
 
-            return format!("The answer is not right. You have {} retries left", retries_left);
-        }
+        testing_env!(context.predecessor_account_id(player).attached_deposit(0).build());
+        assert_eq!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).amount, Some(U128(2000)));
+        assert_eq!(contract.get_quiz_funding(quiz_id).unwrap().balance, U128(0));
     }
 
-    pub fn create_quiz(&mut self, question: String, correct_hash: String, max_prize_amount: String, publish: bool) -> QuizId {
-        self.check_owner();
+    #[test]
+    fn pull_payouts_accrue_until_claimed() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
 
-        let status = if publish { QuizStatus::Published } else { QuizStatus::Unpublished };
-        let quiz_id = self.current_quiz_id;
-        let existing_quiz = self.quizzes.insert(&quiz_id, &Quiz {
-            question, correct_hash, max_prize_amount: max_prize_amount.parse::<u128>().unwrap(), status
-        });
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
 
-        assert!(existing_quiz.is_none(), "Quiz with the same quiz_id already exists");
+        let mut contract = QuizContract::new(account_id);
+        contract.set_pull_payouts(true);
+        let first = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+        let second = contract.create_quiz("What is the capital of Germany".to_owned(), vec!["dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2".to_owned()], Some(U128(50)), None, true, None);
 
-        if publish {
-            self.published_quiz_ids.insert(&quiz_id);
-        }
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(0).build());
+        assert_eq!(contract.submit_answer(first, "Paris".to_owned(), None).amount, Some(U128(100)));
+        assert_eq!(contract.submit_answer(second, "Berlin".to_owned(), None).amount, Some(U128(50)));
+        assert!(transfer_receipts().is_empty());
+        assert_eq!(contract.get_payout_preference(player.clone()).accrued, U128(150));
 
-        self.current_quiz_id += 1;
+        assert_eq!(contract.claim_prizes(), U128(150));
+        let receipts = transfer_receipts();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].actions, vec![near_sdk::mock::VmAction::Transfer { deposit: 150 }]);
+    }
 
-        quiz_id
+    #[test]
+    fn failed_prize_transfer_becomes_claimable() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let context = get_context(account_id.clone(), false);
+        testing_env!(context.build(), near_sdk::VMConfig::test(), near_sdk::RuntimeFeesConfig::test(), Default::default(), vec![PromiseResult::Failed]);
+
+        let mut contract = QuizContract::new(account_id);
+        contract.on_prize_transfer(player.clone(), U128(100));
+        assert_eq!(contract.get_payout_preference(player.clone()).accrued, U128(100));
+
+        testing_env!(context.clone().predecessor_account_id(player.clone()).attached_deposit(0).build());
+        assert_eq!(contract.claim_prizes(), U128(100));
+        assert_eq!(contract.get_payout_preference(player).accrued, U128(0));
     }
 
-    pub fn get_quiz_status(&self, quiz_id: QuizId) -> Option<QuizStatus> {
-        if let Some(quiz) = self.quizzes.get(&quiz_id) {
-            return Some(quiz.status)
-        }
+    #[test]
+    fn payout_cap_limits_account_winnings() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
 
-        None
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        contract.set_payout_cap(Some(PayoutCap { amount: U128(150), per_season: false }));
+        let first = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+        let second = contract.create_quiz("What is the capital of Germany".to_owned(), vec!["dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2".to_owned()], Some(U128(100)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(0).build());
+        assert_eq!(contract.submit_answer(first, "Paris".to_owned(), None).amount, Some(U128(100)));
+        assert_eq!(contract.submit_answer(second, "Berlin".to_owned(), None).amount, Some(U128(50)));
+        assert_eq!(contract.get_payout_records(1, 1)[0].modifiers_applied, vec![("account_cap".to_owned(), -5_000)]);
+        assert_eq!(contract.get_winnings(player.clone()), Winnings { total: U128(150), season_total: U128(150), remaining: Some(U128(0)) });
     }
 
-    pub fn publish_quiz(&mut self, quiz_id: QuizId) {
-        self.check_owner();
+    #[test]
+    fn sponsors_are_credited_with_their_message() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let sponsor = AccountId::new_unchecked("sponsor.near".to_owned());
 
-        let mut quiz = self.quizzes.get(&quiz_id).expect("No such quiz found");
-        if quiz.status == QuizStatus::Unpublished {
-            quiz.status = QuizStatus::Published;
-            self.published_quiz_ids.insert(&quiz_id);
-        }
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_timestamp(7).attached_deposit(100).build());
 
-        self.quizzes.insert(&quiz_id, &quiz);
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(sponsor.clone()).attached_deposit(50).build());
+        assert_eq!(contract.sponsor_quiz(quiz_id, Some(" Brought to you by Sponsor ".to_owned())), U128(150));
+        contract.sponsor_quiz(quiz_id, Some("".to_owned()));
+
+        assert_eq!(contract.get_sponsors(quiz_id), vec![
+            Sponsorship { account_id: sponsor.clone(), amount: U128(50), message: Some("Brought to you by Sponsor".to_owned()), timestamp: 7 },
+            Sponsorship { account_id: sponsor, amount: U128(50), message: None, timestamp: 7 }
+        ]);
+        assert_eq!(contract.get_quiz_funding(quiz_id).unwrap().funders, 2);
     }
 
-    pub fn get_published_quizzes(&self) -> PublishedQuizzes {
-        let quiz_ids = self.published_quiz_ids.to_vec();
-        let mut quizzes = vec![];
-        for quiz_id in quiz_ids {
-            let quiz = self.quizzes.get(&quiz_id).unwrap_or_else(|| env::panic_str("Cannot load quiz"));
-            let json_quiz = JsonQuiz {
-                quiz_id,
-                question: quiz.question,
-                prize_amount: quiz.max_prize_amount.to_string()
-            };
-            quizzes.push(json_quiz);
-        }
-        PublishedQuizzes { 
-            quizzes
-        }
+    #[test]
+    fn large_prizes_vest_in_tranches() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+        let week = 7 * 86_400;
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.block_timestamp(0).build());
+
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { vesting: Some(VestingPolicy { threshold: U128(500), tranches: 4, interval_sec: week }), ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1000)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(0).build());
+        assert_eq!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).amount, Some(U128(1000)));
+        assert!(transfer_receipts().is_empty());
+        assert_eq!(contract_error(|| { contract.claim_vested(); }), QuizError::NothingToClaim);
+
+        testing_env!(context.block_timestamp(2 * week * NANOS_PER_SEC).build());
+        assert_eq!(contract.get_vesting(player.clone()), vec![JsonVestingGrant {
+            quiz_id,
+            total: U128(1000),
+            claimed: U128(0),
+            unlocked: U128(500),
+            next_unlock_at: Some(3 * week * NANOS_PER_SEC)
+        }]);
+        assert_eq!(contract.claim_vested(), U128(500));
+
+        testing_env!(context.block_timestamp(10 * week * NANOS_PER_SEC).build());
+        assert_eq!(contract.claim_vested(), U128(500));
+        assert!(contract.get_vesting(player).is_empty());
     }
 
-    #[private]
-    pub fn check_owner(&self) {
-        assert_eq!(self.owner_id, env::predecessor_account_id(), "This method can only be called by owner");
+    #[test]
+    fn premium_quiz_needs_a_stake() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+        let cheater = AccountId::new_unchecked("carol.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id.clone());
+        let options = QuizOptions { stake_required: Some(U128(50)), ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(0).build());
+        assert_eq!(
+            contract_error(|| { contract.submit_answer(quiz_id, "Paris".to_owned(), None); }),
+            QuizError::StakeRequired { quiz_id, required: U128(50) }
+        );
+        testing_env!(context.attached_deposit(50).build());
+        assert_eq!(contract.stake_for_quiz(quiz_id), U128(50));
+        testing_env!(context.attached_deposit(0).build());
+        assert!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).correct);
+        assert_eq!(contract_error(|| { contract.withdraw_stake(quiz_id); }), QuizError::EscrowLocked { quiz_id });
+
+        testing_env!(context.predecessor_account_id(cheater.clone()).attached_deposit(50).build());
+        contract.stake_for_quiz(quiz_id);
+        testing_env!(context.predecessor_account_id(account_id.clone()).attached_deposit(0).build());
+        assert_eq!(contract.slash_stake(quiz_id, cheater.clone(), Some("shared answers".to_owned())), U128(50));
+        assert_eq!(contract.get_stake(quiz_id, cheater), U128(0));
+        contract.close_quiz(quiz_id);
+
+        testing_env!(context.predecessor_account_id(player.clone()).build());
+        assert_eq!(contract.withdraw_stake(quiz_id), U128(50));
+        assert_eq!(contract.get_stake(quiz_id, player), U128(0));
     }
-}
 
-#[cfg(all(test, not(target_arch = "wasm32")))]
-mod tests {
-    use std::panic::PanicInfo;
+    #[test]
+    fn entry_fees_build_a_shared_pool() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let alice = AccountId::new_unchecked("alice.near".to_owned());
+        let carol = AccountId::new_unchecked("carol.near".to_owned());
 
-    use super::*;
-    use near_sdk::test_utils::VMContextBuilder;
-    use near_sdk::{testing_env};
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.attached_deposit(0).build());
 
-    fn get_context(signer: AccountId, is_view: bool) -> VMContextBuilder {
-        let mut builder = VMContextBuilder::new();
-        builder.predecessor_account_id(signer);
-        builder.is_view(is_view);
-        builder
+        let mut contract = QuizContract::new(account_id.clone());
+        let options = QuizOptions {
+            prize_split: Some(PrizeSplit::Equal),
+            max_winners: Some(2),
+            fee_schedule: vec![U128(30), U128(30), U128(30)],
+            pool_entry_fees: true,
+            ..Default::default()
+        };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(0)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(alice.clone()).attached_deposit(30).build());
+        contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+        testing_env!(context.predecessor_account_id(carol).build());
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+        assert_eq!(contract.get_quiz_funding(quiz_id).unwrap().balance, U128(90));
+        assert_eq!(contract.get_withdrawable_balance(), U128(0));
+
+        assert_eq!(contract.distribute_split(quiz_id), U128(90));
+        assert_eq!(contract.get_payout_records(0, 2).iter().map(|record| record.final_amount).collect::<Vec<_>>(), vec![U128(45), U128(45)]);
     }
 
     #[test]
-    fn create_new_contract() {
+    fn payout_schedule_sets_each_attempt_tier() {
         let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
 
-        let context = get_context(account_id.clone(), false);
+        let mut context = get_context(account_id.clone(), false);
         testing_env!(context.build());
 
-        let contract = QuizContract::new(account_id);
-        assert_eq!(contract.owner_id, env::signer_account_id());
+        let mut contract = QuizContract::new(account_id);
+        let options = QuizOptions { max_retries: Some(4), payout_schedule: vec![10_000, 9_000, 8_000, 1_000], ..Default::default() };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1000)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(player).attached_deposit(0).build());
+        contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+        contract.submit_answer(quiz_id, "Rome".to_owned(), None);
+        assert_eq!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).amount, Some(U128(800)));
+        assert_eq!(contract.get_payout_records(0, 1)[0].modifiers_applied, vec![("payout_schedule".to_owned(), -2_000)]);
     }
 
     #[test]
-    fn get_published_quizzes_empty() {
+    fn registration_is_required_once_enabled() {
         let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
 
-        let context = get_context(account_id.clone(), true);
+        let mut context = get_context(account_id.clone(), false);
         testing_env!(context.build());
 
-        let contract = QuizContract::new(account_id);
+        let mut contract = QuizContract::new(account_id);
+        contract.set_storage_required(true);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
 
-        let published_quizzes = contract.get_published_quizzes();
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(0).build());
+        assert_eq!(
+            contract_error(|| { contract.submit_answer(quiz_id, "Paris".to_owned(), None); }),
+            QuizError::StorageNotRegistered { account_id: player.clone() }
+        );
 
-        assert_eq!(published_quizzes.quizzes.len(), 0);
+        testing_env!(context.attached_deposit(STORAGE_BALANCE + 5).build());
+        let balance = contract.storage_deposit(None, None);
+        assert_eq!(balance, StorageBalance { total: U128(STORAGE_BALANCE), available: U128(0) });
+        assert_eq!(transfer_receipts()[0].actions, vec![near_sdk::mock::VmAction::Transfer { deposit: 5 }]);
+
+        testing_env!(context.attached_deposit(0).build());
+        assert!(contract.submit_answer(quiz_id, "Paris".to_owned(), None).correct);
+
+        testing_env!(context.attached_deposit(1).build());
+        assert!(contract.storage_unregister(None));
+        assert_eq!(contract.storage_balance_of(player), None);
     }
 
     #[test]
-    fn get_published_quizzes() {
+    fn entry_fees_are_refunded_to_non_winners() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let alice = AccountId::new_unchecked("alice.near".to_owned());
+        let carol = AccountId::new_unchecked("carol.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id.clone());
+        let options = QuizOptions {
+            fee_schedule: vec![U128(100), U128(100), U128(100)],
+            entry_refund_rake_bps: Some(1_000),
+            ..Default::default()
+        };
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(500)), None, true, Some(options));
+
+        testing_env!(context.predecessor_account_id(alice.clone()).attached_deposit(100).build());
+        contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+        testing_env!(context.predecessor_account_id(carol.clone()).build());
+        contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+        contract.submit_answer(quiz_id, "Rome".to_owned(), None);
+        assert_eq!(contract.get_entry_fees(quiz_id, alice.clone()), U128(0));
+        assert_eq!(contract.get_entry_fees(quiz_id, carol.clone()), U128(200));
+        assert_eq!(contract_error(|| { contract.refund_entry(quiz_id); }), QuizError::EscrowLocked { quiz_id });
+
+        testing_env!(context.predecessor_account_id(account_id).attached_deposit(0).build());
+        contract.close_quiz(quiz_id);
+
+        testing_env!(context.predecessor_account_id(carol.clone()).build());
+        assert_eq!(contract.refund_entry(quiz_id), U128(180));
+        assert_eq!(contract.get_withdrawable_balance(), U128(220));
+        assert_eq!(contract_error(|| { contract.refund_entry(quiz_id); }), QuizError::NothingToWithdraw);
+
+        testing_env!(context.predecessor_account_id(alice).build());
+        assert_eq!(contract_error(|| { contract.refund_entry(quiz_id); }), QuizError::NothingToWithdraw);
+    }
+
+    #[test]
+    fn referrers_earn_from_first_wins() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let alice = AccountId::new_unchecked("alice.near".to_owned());
+        let carol = AccountId::new_unchecked("carol.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id.clone());
+        contract.set_treasury(AccountId::new_unchecked("treasury.near".to_owned()), 1_000);
+        contract.set_referral_config(ReferralConfig { bonus_bps: 500, rewarded_wins: 1 });
+        let first = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1000)), None, true, None);
+        let second = contract.create_quiz("Which city hosts the Louvre".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(1000)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(alice.clone()).attached_deposit(0).build());
+        contract.register_referral(carol.clone());
+        contract.submit_answer(first, "Paris".to_owned(), None);
+        contract.submit_answer(second, "Paris".to_owned(), None);
+        assert_eq!(contract.get_referral(alice), Some(Referral { referrer: carol.clone(), rewarded_wins: 1 }));
+        assert_eq!(contract.get_referrer_stats(carol), ReferrerStats { referred: 1, earned: U128(50) });
+        assert_eq!(contract.get_accrued_fees().near, U128(150));
+    }
+
+    #[test]
+    #[should_panic(expected = "alice.near was referred by you")]
+    fn register_circular_referral() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let alice = AccountId::new_unchecked("alice.near".to_owned());
+        let carol = AccountId::new_unchecked("carol.near".to_owned());
+
+        let mut context = get_context(alice.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        contract.register_referral(carol.clone());
+
+        testing_env!(context.predecessor_account_id(carol).build());
+        contract.register_referral(alice);
+    }
+
+    #[test]
+    fn budget_tracks_payouts_and_pending_splits() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
+
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.attached_deposit(600).build());
+
+        let mut contract = QuizContract::new(account_id);
+        let single = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(300)), None, true, Some(QuizOptions { max_winners: Some(2), ..Default::default() }));
+        let split = contract.create_quiz("Which city hosts the Louvre".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(300)), None, true, Some(QuizOptions { max_winners: Some(2), prize_split: Some(PrizeSplit::Equal), ..Default::default() }));
+
+        testing_env!(context.predecessor_account_id(player).attached_deposit(0).build());
+        contract.submit_answer(single, "Paris".to_owned(), None);
+        contract.submit_answer(split, "Paris".to_owned(), None);
+
+        assert_eq!(
+            contract.get_quiz_budget(single),
+            Some(QuizBudget { token: None, funded: U128(600), paid_out: U128(300), pending_claims: U128(0), remaining: U128(300) })
+        );
+        assert_eq!(
+            contract.get_quiz_budget(split),
+            Some(QuizBudget { token: None, funded: U128(600), paid_out: U128(0), pending_claims: U128(600), remaining: U128(0) })
+        );
+    }
+
+    #[test]
+    fn get_quiz_hides_drafts() {
         let account_id = AccountId::new_unchecked("bob.near".to_owned());
 
         let context = get_context(account_id.clone(), false);
         testing_env!(context.build());
 
         let mut contract = QuizContract::new(account_id);
-        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), "5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned(), "1".to_owned(), true);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+        let draft = contract.create_quiz("Which city hosts the Louvre".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, false, None);
 
-        let published_quizzes = contract.get_published_quizzes();
-        assert_eq!(published_quizzes.quizzes.len(), 1);
-        assert_eq!(published_quizzes.quizzes[0].quiz_id, quiz_id);
+        let quiz = contract.get_quiz(quiz_id).unwrap();
+        assert_eq!(quiz.status, QuizStatus::Published);
+        assert_eq!(quiz.max_retries, 3);
+        assert_eq!(contract.get_quiz(draft), None);
+        assert_eq!(contract.get_quiz(draft + 1), None);
+
+        let drafts = contract.get_unpublished_quizzes(0, 10);
+        assert_eq!(drafts.iter().map(|draft| draft.quiz_id).collect::<Vec<_>>(), vec![draft]);
+
+        testing_env!(get_context(AccountId::new_unchecked("alice.near".to_owned()), false).build());
+        assert_eq!(contract.get_unpublished_quizzes(0, 10).len(), 1);
     }
 
     #[test]
-    fn create_new_quiz() {
+    fn get_published_quizzes_pages() {
         let account_id = AccountId::new_unchecked("bob.near".to_owned());
 
         let context = get_context(account_id.clone(), false);
         testing_env!(context.build());
 
         let mut contract = QuizContract::new(account_id);
-        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), "5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned(), "1".to_owned(), true);
+        let questions = ["What is the capital of France", "Which city hosts the Louvre", "Where is the Eiffel Tower"];
+        let quiz_ids: Vec<QuizId> = questions.iter()
+            .map(|question| contract.create_quiz(question.to_string(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None))
+            .collect();
 
-        let quiz = contract.quizzes.get(&quiz_id).unwrap();
-        assert_eq!(quiz.question, "What is the capital of France".to_owned());
+        let page = contract.get_published_quizzes(1, 5);
+        assert_eq!(page.total, 3);
+        assert_eq!(page.quizzes.iter().map(|quiz| quiz.quiz_id).collect::<Vec<_>>(), quiz_ids[1..].to_vec());
+        assert!(contract.get_published_quizzes(3, 5).quizzes.is_empty());
     }
 
     #[test]
-    #[should_panic]
-    fn crate_quiz_only_by_owner() {
-        let f  = |_: &PanicInfo| {};
-        std::panic::set_hook(Box::new(f));
+    fn get_solved_quizzes_lists_completions() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
 
+        let mut context = get_context(account_id.clone(), false);
+        testing_env!(context.build());
+
+        let mut contract = QuizContract::new(account_id);
+        let first = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+        let second = contract.create_quiz("Which city hosts the Louvre".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+        contract.create_quiz("Where is the Eiffel Tower".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(0).build());
+        assert!(contract.get_solved_quizzes(player.clone(), 0, 10).is_empty());
+        contract.submit_answer(first, "Paris".to_owned(), None);
+        contract.submit_answer(second, "Paris".to_owned(), None);
+
+        assert_eq!(contract.get_solved_quizzes(player.clone(), 0, 10), vec![first, second]);
+        assert_eq!(contract.get_solved_quizzes(player, 1, 10), vec![second]);
+    }
+
+    #[test]
+    fn get_retries_left_counts_down() {
         let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
 
-        let context = get_context(account_id.clone(), false);
+        let mut context = get_context(account_id.clone(), false);
         testing_env!(context.build());
 
         let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
+
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(0).build());
+        assert_eq!(contract.get_retries_left(player.clone(), quiz_id), Some(3));
+        contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+        assert_eq!(contract.get_retries_left(player.clone(), quiz_id), Some(2));
+        assert_eq!(contract.get_retries_left(player, quiz_id + 1), None);
+    }
 
+    #[test]
+    fn leaderboard_ranks_by_solves_then_winnings() {
+        let account_id = AccountId::new_unchecked("bob.near".to_owned());
         let alice = AccountId::new_unchecked("alice.near".to_owned());
+        let carol = AccountId::new_unchecked("carol.near".to_owned());
 
-        let context = get_context(alice, false);
+        let mut context = get_context(account_id.clone(), false);
         testing_env!(context.build());
 
-        contract.create_quiz("What is the capital of France".to_owned(), "5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned(), "1".to_owned(), true);
+        let mut contract = QuizContract::new(account_id);
+        let options = || Some(QuizOptions { max_winners: Some(2), ..Default::default() });
+        let first = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, options());
+        let second = contract.create_quiz("Which city hosts the Louvre".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, options());
+
+        testing_env!(context.predecessor_account_id(alice.clone()).attached_deposit(0).build());
+        contract.submit_answer(first, "Berlin".to_owned(), None);
+        contract.submit_answer(first, "Paris".to_owned(), None);
+        testing_env!(context.predecessor_account_id(carol.clone()).build());
+        contract.submit_answer(first, "Paris".to_owned(), None);
+        assert_eq!(contract.get_leaderboard(10).iter().map(|entry| entry.account_id.clone()).collect::<Vec<_>>(), vec![carol.clone(), alice.clone()]);
+
+        testing_env!(context.predecessor_account_id(alice.clone()).build());
+        contract.submit_answer(second, "Paris".to_owned(), None);
+        assert_eq!(contract.get_leaderboard(1), vec![LeaderboardEntry { account_id: alice, solved: 2, winnings: U128(150) }]);
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("dave.near".to_owned())).build());
+        assert!(contract.submit_answer(first, "Paris".to_owned(), None).prize_pool_exhausted);
+        let dave = contract.get_leaderboard(10).into_iter().find(|entry| entry.account_id.as_str() == "dave.near");
+        assert_eq!(dave.map(|entry| (entry.solved, entry.winnings)), Some((1, U128(0))));
     }
 
     #[test]
-    fn get_publish_status() {
+    fn account_stats_sum_up_a_profile() {
         let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let player = AccountId::new_unchecked("alice.near".to_owned());
 
-        let context = get_context(account_id.clone(), false);
+        let mut context = get_context(account_id.clone(), false);
         testing_env!(context.build());
 
         let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
 
-        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), "5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned(), "1".to_owned(), false);
-        assert_eq!(contract.get_quiz_status(quiz_id).unwrap(), QuizStatus::Unpublished);
+        testing_env!(context.predecessor_account_id(player.clone()).attached_deposit(0).build());
+        contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
 
-        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), "5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned(), "1".to_owned(), true);
-        assert_eq!(contract.get_quiz_status(quiz_id).unwrap(), QuizStatus::Published);
+        assert_eq!(
+            contract.get_account_stats(player),
+            AccountStats { solved: 1, attempts: 2, winnings: U128(50), streak: Streak { current: 1, best: 1 }, rank: Some(1) }
+        );
     }
 
     #[test]
-    #[should_panic]
-    fn submit_answer_to_unpublished() {
-        let f  = |_: &PanicInfo| {};
-        std::panic::set_hook(Box::new(f));
-        
+    fn account_stats_count_solves_on_exhausted_quizzes() {
         let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let alice = AccountId::new_unchecked("alice.near".to_owned());
+        let carol = AccountId::new_unchecked("carol.near".to_owned());
 
-        let context = get_context(account_id.clone(), false);
+        let mut context = get_context(account_id.clone(), false);
         testing_env!(context.build());
 
         let mut contract = QuizContract::new(account_id);
-        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), "5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned(), "1".to_owned(), false);
+        let first = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(QuizOptions { max_winners: Some(1), ..Default::default() }));
+        let second = contract.create_quiz("Which city hosts the Louvre".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
 
-        contract.submit_answer(quiz_id, "Paris".to_owned());
+        testing_env!(context.predecessor_account_id(alice).attached_deposit(0).build());
+        contract.submit_answer(first, "Paris".to_owned(), None);
+        testing_env!(context.predecessor_account_id(carol.clone()).build());
+        contract.submit_answer(second, "Paris".to_owned(), None);
+        assert!(contract.submit_answer(first, "Paris".to_owned(), None).prize_pool_exhausted);
+
+        let stats = contract.get_account_stats(carol);
+        assert_eq!((stats.solved, stats.attempts, stats.winnings), (2, 2, U128(100)));
+        assert_eq!(stats.streak, Streak { current: 2, best: 2 });
     }
 
     #[test]
-    fn submit_correct_answer_to_published() {
+    fn quiz_stats_count_attempts_and_participants() {
         let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let alice = AccountId::new_unchecked("alice.near".to_owned());
+        let carol = AccountId::new_unchecked("carol.near".to_owned());
 
-        let context = get_context(account_id.clone(), false);
+        let mut context = get_context(account_id.clone(), false);
         testing_env!(context.build());
 
-        let mut contract = QuizContract::new(account_id.clone());
-        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), "5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned(), "1".to_owned(), true);
+        let mut contract = QuizContract::new(account_id);
+        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
 
-        contract.submit_answer(quiz_id.clone(), "Paris".to_owned());
+        testing_env!(context.predecessor_account_id(alice).attached_deposit(0).build());
+        contract.submit_answer(quiz_id, "Berlin".to_owned(), None);
+        contract.submit_answer(quiz_id, "Paris".to_owned(), None);
+        testing_env!(context.predecessor_account_id(carol).build());
+        contract.submit_answer(quiz_id, "Rome".to_owned(), None);
 
-        assert_eq!(contract.solved_quizzes.get(&account_id).unwrap().contains(&quiz_id), true);
+        assert_eq!(
+            contract.get_quiz_stats(quiz_id),
+            Some(QuizStats { attempts: 3, practice_attempts: 0, participants: 2, solver_count: 1, winner_count: 1, paid_out: U128(50) })
+        );
+        assert_eq!(contract.get_quiz_stats(quiz_id + 1), None);
     }
 
     #[test]
-    fn submit_incorrect_answer_to_published() {
+    fn contract_stats_add_up_across_quizzes() {
         let account_id = AccountId::new_unchecked("bob.near".to_owned());
+        let alice = AccountId::new_unchecked("alice.near".to_owned());
+        let carol = AccountId::new_unchecked("carol.near".to_owned());
 
-        let context = get_context(account_id.clone(), false);
+        let mut context = get_context(account_id.clone(), false);
         testing_env!(context.build());
 
-        let mut contract = QuizContract::new(account_id.clone());
-        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), "5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned(), "1".to_owned(), true);
+        let mut contract = QuizContract::new(account_id);
+        let options = || Some(QuizOptions { max_winners: Some(2), ..Default::default() });
+        let first = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, options());
+        let second = contract.create_quiz("Which city hosts the Louvre".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, options());
+        contract.create_quiz("Where is the Eiffel Tower".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, false, None);
 
-        contract.submit_answer(quiz_id.clone(), "Berlin".to_owned());
+        testing_env!(context.predecessor_account_id(alice).attached_deposit(0).build());
+        contract.submit_answer(first, "Paris".to_owned(), None);
+        contract.submit_answer(second, "Berlin".to_owned(), None);
+        contract.submit_answer(second, "Paris".to_owned(), None);
+        testing_env!(context.predecessor_account_id(carol).build());
+        contract.submit_answer(first, "Paris".to_owned(), None);
 
-        assert_eq!(contract.retries_left.get(&account_id).unwrap().get(&quiz_id).unwrap(), 2);
-        contract.submit_answer(quiz_id.clone(), "Madrid".to_owned());
-        assert_eq!(contract.retries_left.get(&account_id).unwrap().get(&quiz_id).unwrap(), 1);
+        assert_eq!(contract.get_stats(), ContractStats {
+            total_quizzes: 3,
+            published_quizzes: 2,
+            total_attempts: 4,
+            total_solves: 3,
+            unique_solvers: 2,
+            near_distributed: U128(250)
+        });
     }
 
     #[test]
-    fn publish_unpublished_quiz() {
+    fn total_solves_match_quiz_solver_counts() {
         let account_id = AccountId::new_unchecked("bob.near".to_owned());
 
-        let context = get_context(account_id.clone(), false);
+        let mut context = get_context(account_id.clone(), false);
         testing_env!(context.build());
 
-        let mut contract = QuizContract::new(account_id.clone());
-        let quiz_id = contract.create_quiz("What is the capital of France".to_owned(), "5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned(), "1".to_owned(), false);
+        let mut contract = QuizContract::new(account_id);
+        let capped = contract.create_quiz("What is the capital of France".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, Some(QuizOptions { max_winners: Some(1), ..Default::default() }));
+        let open = contract.create_quiz("Which city hosts the Louvre".to_owned(), vec!["5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned()], Some(U128(100)), None, true, None);
 
-        assert_eq!(contract.quizzes.get(&quiz_id).unwrap().status, QuizStatus::Unpublished);
-        contract.publish_quiz(quiz_id.clone());
-        assert_eq!(contract.quizzes.get(&quiz_id).unwrap().status, QuizStatus::Published);
+        for player in ["alice.near", "carol.near", "dave.near"] {
+            testing_env!(context.predecessor_account_id(AccountId::new_unchecked(player.to_owned())).attached_deposit(0).build());
+            contract.submit_answer(capped, "Paris".to_owned(), None);
+            contract.submit_answer(open, "Paris".to_owned(), None);
+        }
+
+        let solver_counts: u64 = [capped, open].iter().map(|&quiz_id| contract.get_quiz_stats(quiz_id).unwrap().solver_count).sum();
+        assert_eq!(contract.get_stats().total_solves, solver_counts);
+        assert_eq!(contract.get_stats().total_solves, 6);
     }
 }
\ No newline at end of file
@@ -0,0 +1,221 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{LookupMap, UnorderedSet};
+use near_sdk::json_types::U128;
+use near_sdk::{env, near_bindgen, AccountId};
+
+use crate::*;
+
+pub(crate) const STATE_VERSION: u32 = 1;
+const STATE_KEY: &[u8] = b"STATE";
+
+// Layout of the first deployed release, before any of the per-quiz options existed.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub(crate) struct BaselineQuiz {
+    pub(crate) status: QuizStatus,
+    pub(crate) question: String,
+    pub(crate) correct_hash: String,
+    pub(crate) max_prize_amount: u128
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub(crate) struct BaselineState {
+    pub(crate) owner_id: AccountId,
+    pub(crate) quizzes: LookupMap<QuizId, BaselineQuiz>,
+    pub(crate) published_quiz_ids: UnorderedSet<QuizId>,
+    pub(crate) solved_quizzes: LookupMap<AccountId, UnorderedSet<QuizId>>,
+    pub(crate) retries_left: LookupMap<AccountId, LookupMap<QuizId, usize>>,
+    pub(crate) current_quiz_id: QuizId
+}
+
+// Baseline retry counts are read in place rather than copied, since a LookupMap cannot list the
+// accounts that have one.
+pub(crate) fn baseline_retries_left(account_id: &AccountId, quiz_id: QuizId) -> Option<usize> {
+    LookupMap::<AccountId, LookupMap<QuizId, usize>>::new(StorageKey::RetriesLeft)
+        .get(account_id)
+        .and_then(|records| records.get(&quiz_id))
+}
+
+#[near_bindgen]
+impl QuizContract {
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        // The baseline predates state_version, so it is recognised by parsing: try_from_slice
+        // rejects any later layout, which carries more fields after the baseline ones.
+        let state = env::storage_read(STATE_KEY).expect("No contract state to migrate");
+        let Ok(mut old) = BaselineState::try_from_slice(&state) else {
+            let version = QuizContract::try_from_slice(&state).ok().map(|contract| contract.state_version);
+            assert!(version != Some(STATE_VERSION), "Contract state is already at version {}", STATE_VERSION);
+            panic!("Contract state is in an unknown layout");
+        };
+        let mut contract = Self::new(old.owner_id);
+        contract.published_quiz_ids = old.published_quiz_ids;
+        contract.solved_quizzes = old.solved_quizzes;
+        contract.current_quiz_id = old.current_quiz_id;
+
+        // Baseline prizes came out of the contract balance, so each quiz is escrowed one prize
+        // that is booked to the contract itself.
+        for quiz_id in 0..old.current_quiz_id {
+            // Removing first keeps insert from decoding the baseline value as a current Quiz.
+            let Some(old_quiz) = old.quizzes.remove(&quiz_id) else {
+                continue;
+            };
+
+            let input = QuizInput {
+                question: old_quiz.question,
+                correct_hashes: vec![old_quiz.correct_hash],
+                max_prize_amount: Some(U128(old_quiz.max_prize_amount)),
+                max_prize_near: None,
+                publish: old_quiz.status == QuizStatus::Published,
+                options: None
+            };
+            let (mut quiz, _) = build_quiz(input, DEFAULT_RETRIES);
+            quiz.escrow = old_quiz.max_prize_amount;
+            quiz.funded = old_quiz.max_prize_amount;
+
+            contract.index_question(quiz_id, &quiz.question, true);
+            contract.quizzes.insert(&quiz_id, &quiz);
+            contract.record_transition(quiz_id, QuizTransition::Created);
+            if quiz.escrow > 0 {
+                contract.record_funding(quiz_id, &env::current_account_id(), quiz.escrow);
+            }
+        }
+
+        contract
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    #[test]
+    fn migrate_from_baseline() {
+        let owner = AccountId::new_unchecked("bob.near".to_owned());
+        let alice = AccountId::new_unchecked("alice.near".to_owned());
+        let carol = AccountId::new_unchecked("carol.near".to_owned());
+        let mut context = VMContextBuilder::new();
+        context.predecessor_account_id(env::current_account_id());
+        testing_env!(context.build());
+
+        let mut old = BaselineState {
+            owner_id: owner.clone(),
+            quizzes: LookupMap::new(StorageKey::Quizzes),
+            published_quiz_ids: UnorderedSet::new(StorageKey::PublishedQuizzes),
+            solved_quizzes: LookupMap::new(StorageKey::SolvedQuizzes),
+            retries_left: LookupMap::new(StorageKey::RetriesLeft),
+            current_quiz_id: 2
+        };
+        old.quizzes.insert(&0, &BaselineQuiz {
+            status: QuizStatus::Published,
+            question: "What is the capital of France".to_owned(),
+            correct_hash: "5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned(),
+            max_prize_amount: 100
+        });
+        old.quizzes.insert(&1, &BaselineQuiz {
+            status: QuizStatus::Unpublished,
+            question: "What is the capital of Germany".to_owned(),
+            correct_hash: "dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2".to_owned(),
+            max_prize_amount: 100
+        });
+        old.published_quiz_ids.insert(&0);
+        let mut solved = UnorderedSet::new(account_prefix(b's', &alice));
+        solved.insert(&0);
+        old.solved_quizzes.insert(&alice, &solved);
+        let mut retries = LookupMap::new(account_prefix(b'r', &carol));
+        retries.insert(&0, &1);
+        old.retries_left.insert(&carol, &retries);
+        env::state_write(&old);
+
+        let mut contract = QuizContract::migrate();
+        assert_eq!(contract.state_version, STATE_VERSION);
+        assert_eq!(contract.owner_id, owner);
+        assert_eq!(contract.current_quiz_id, 2);
+        assert_eq!(contract.get_published_quizzes(0, 10).quizzes.iter().map(|quiz| quiz.quiz_id).collect::<Vec<_>>(), vec![0]);
+        assert_eq!(contract.get_quiz_status(1), Some(QuizStatus::Unpublished));
+        assert_eq!(contract.get_solved_quizzes(alice.clone(), 0, 10), vec![0]);
+        assert_eq!(contract.get_retries_left(carol.clone(), 0), Some(1));
+        assert_eq!(contract.get_retries_left(alice, 1), Some(DEFAULT_RETRIES));
+        assert_eq!(contract.quiz_funders.get(&0).unwrap().get(&env::current_account_id()), Some(100));
+
+        testing_env!(context.predecessor_account_id(AccountId::new_unchecked("dave.near".to_owned())).build());
+        let result = contract.submit_answer(0, "Paris".to_owned(), None);
+        assert!(result.correct);
+        assert_eq!(result.amount, Some(U128(100)));
+    }
+
+    // A baseline with one published quiz that `player` has run out of retries on.
+    fn write_exhausted_baseline(owner: &AccountId, player: &AccountId) {
+        let mut old = BaselineState {
+            owner_id: owner.clone(),
+            quizzes: LookupMap::new(StorageKey::Quizzes),
+            published_quiz_ids: UnorderedSet::new(StorageKey::PublishedQuizzes),
+            solved_quizzes: LookupMap::new(StorageKey::SolvedQuizzes),
+            retries_left: LookupMap::new(StorageKey::RetriesLeft),
+            current_quiz_id: 1
+        };
+        old.quizzes.insert(&0, &BaselineQuiz {
+            status: QuizStatus::Published,
+            question: "What is the capital of Germany".to_owned(),
+            correct_hash: "5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1".to_owned(),
+            max_prize_amount: 100
+        });
+        old.published_quiz_ids.insert(&0);
+        let mut retries = LookupMap::new(account_prefix(b'r', player));
+        retries.insert(&0, &0);
+        old.retries_left.insert(player, &retries);
+        env::state_write(&old);
+    }
+
+    #[test]
+    fn correct_answer_resets_baseline_retries() {
+        let owner = AccountId::new_unchecked("bob.near".to_owned());
+        let carol = AccountId::new_unchecked("carol.near".to_owned());
+        let mut context = VMContextBuilder::new();
+        context.predecessor_account_id(env::current_account_id());
+        testing_env!(context.build());
+        write_exhausted_baseline(&owner, &carol);
+
+        let mut contract = QuizContract::migrate();
+        contract.track_participant(0, &carol);
+        assert_eq!(contract.get_retries_left(carol.clone(), 0), Some(0));
+
+        testing_env!(context.predecessor_account_id(owner).build());
+        let reset = contract.correct_answer(0, vec!["dad114b6ed7342bac65c79575f6c7ff761ec26b52c1f5f7a9110532973d05df2".to_owned()]);
+        assert_eq!(reset, vec![carol.clone()]);
+        assert_eq!(contract.get_retries_left(carol, 0), Some(DEFAULT_RETRIES));
+    }
+
+    #[test]
+    fn buy_retry_with_baseline_retries() {
+        let owner = AccountId::new_unchecked("bob.near".to_owned());
+        let carol = AccountId::new_unchecked("carol.near".to_owned());
+        let mut context = VMContextBuilder::new();
+        context.predecessor_account_id(env::current_account_id());
+        testing_env!(context.build());
+        write_exhausted_baseline(&owner, &carol);
+
+        let mut contract = QuizContract::migrate();
+        let mut quiz = contract.quizzes.get(&0).unwrap();
+        quiz.retry_price = Some(10);
+        contract.quizzes.insert(&0, &quiz);
+
+        testing_env!(context.predecessor_account_id(carol.clone()).attached_deposit(10).build());
+        contract.buy_retry(0);
+        assert_eq!(contract.get_retries_left(carol, 0), Some(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract state is already at version 1")]
+    fn migrate_twice() {
+        let owner = AccountId::new_unchecked("bob.near".to_owned());
+        let mut context = VMContextBuilder::new();
+        context.predecessor_account_id(owner.clone());
+        testing_env!(context.build());
+
+        env::state_write(&QuizContract::new(owner));
+        QuizContract::migrate();
+    }
+}
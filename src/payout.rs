@@ -0,0 +1,425 @@
+use crate::{StalePolicy, TimeDecay};
+
+pub(crate) const BASIS_POINTS: u128 = 10_000;
+
+pub struct PayoutParams<'a> {
+    pub base_prize: u128,
+    pub stage_payout_bps: &'a [u32],
+    pub stale_policy: Option<&'a StalePolicy>,
+    pub retry_decay: bool,
+    pub payout_schedule: &'a [u32]
+}
+
+pub struct PayoutContext {
+    pub revealed_stage: usize,
+    pub unsolved_sec: u64,
+    pub treasury_fee_bps: u16,
+    pub difficulty_bps: u32,
+    pub streak_bps: u32,
+    pub credit_bps: u32,
+    pub hint_penalty_bps: u32,
+    pub time_decay_bps: u32,
+    pub account_cap: Option<u128>,
+    pub pool: Option<u128>
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PayoutBreakdown {
+    pub base_prize: u128,
+    pub modifiers: Vec<(String, i64)>,
+    pub final_amount: u128,
+    pub treasury_fee: u128,
+    pub solver_amount: u128
+}
+
+pub(crate) fn apply_bps(amount: u128, bps: u128) -> u128 {
+    (amount / BASIS_POINTS).saturating_mul(bps).saturating_add(amount % BASIS_POINTS * bps / BASIS_POINTS)
+}
+
+// A boost never lifts the prize above the ceiling, but a ceiling below the base prize is left to the pool cap.
+pub fn boosted_prize(base_prize: u128, policy: Option<&StalePolicy>, unsolved_sec: u64, ceiling: Option<u128>) -> u128 {
+    let Some(policy) = policy else {
+        return base_prize;
+    };
+
+    let increases = (unsolved_sec / policy.after_sec).min(policy.max_increases as u64);
+    let mut amount = base_prize;
+    for _ in 0..increases {
+        amount = apply_bps(amount, BASIS_POINTS + policy.prize_increase_bps as u128);
+    }
+
+    ceiling.map_or(amount, |ceiling| amount.min(ceiling.max(base_prize)))
+}
+
+// The share of the prize left after elapsed_sec of a window_sec decay, never below floor_bps.
+pub fn decay_bps(decay: Option<&TimeDecay>, elapsed_sec: u64, window_sec: u64) -> u32 {
+    let Some(decay) = decay.filter(|_| window_sec > 0) else {
+        return BASIS_POINTS as u32;
+    };
+
+    let mut elapsed = elapsed_sec.min(window_sec);
+    if let Some(step_sec) = decay.step_sec {
+        elapsed -= elapsed % step_sec;
+    }
+    let lost = (BASIS_POINTS as u32 - decay.floor_bps) as u128 * elapsed as u128 / window_sec as u128;
+    BASIS_POINTS as u32 - lost as u32
+}
+
+fn gain_bps(from: u128, to: u128) -> i64 {
+    let gained = to.saturating_sub(from);
+    let bps = gained.checked_mul(BASIS_POINTS).and_then(|scaled| scaled.checked_div(from)).unwrap_or(u128::MAX);
+    i64::try_from(bps).unwrap_or(i64::MAX)
+}
+
+fn stage_bps(params: &PayoutParams, context: &PayoutContext) -> u128 {
+    match context.revealed_stage {
+        0 => BASIS_POINTS,
+        stage => params.stage_payout_bps.get(stage - 1).map_or(BASIS_POINTS, |&bps| bps as u128)
+    }
+}
+
+// Every adjustment to the base prize has to be listed here, so the payout log can always explain the amount.
+pub fn compute_payout(quiz: &PayoutParams, attempt: u8, context: &PayoutContext) -> PayoutBreakdown {
+    let mut amount = quiz.base_prize;
+    let mut modifiers = vec![];
+
+    let boosted = boosted_prize(amount, quiz.stale_policy, context.unsolved_sec, context.pool);
+    if boosted > amount {
+        modifiers.push(("stale_boost".to_owned(), gain_bps(amount, boosted)));
+        amount = boosted;
+    }
+
+    if context.time_decay_bps != BASIS_POINTS as u32 {
+        amount = apply_bps(amount, context.time_decay_bps as u128);
+        modifiers.push(("time_decay".to_owned(), context.time_decay_bps as i64 - BASIS_POINTS as i64));
+    }
+
+    let difficulty_bps = context.difficulty_bps as u128;
+    if difficulty_bps != BASIS_POINTS {
+        amount = apply_bps(amount, difficulty_bps);
+        modifiers.push(("difficulty_multiplier".to_owned(), difficulty_bps as i64 - BASIS_POINTS as i64));
+    }
+
+    if context.streak_bps != BASIS_POINTS as u32 {
+        amount = apply_bps(amount, context.streak_bps as u128);
+        modifiers.push(("streak_multiplier".to_owned(), context.streak_bps as i64 - BASIS_POINTS as i64));
+    }
+
+    let stage_bps = stage_bps(quiz, context);
+    if stage_bps != BASIS_POINTS {
+        amount = apply_bps(amount, stage_bps);
+        modifiers.push(("stage_multiplier".to_owned(), stage_bps as i64 - BASIS_POINTS as i64));
+    }
+
+    if !quiz.payout_schedule.is_empty() {
+        let index = (attempt.max(1) as usize - 1).min(quiz.payout_schedule.len() - 1);
+        let bps = quiz.payout_schedule[index] as u128;
+        if bps != BASIS_POINTS {
+            amount = apply_bps(amount, bps);
+            modifiers.push(("payout_schedule".to_owned(), bps as i64 - BASIS_POINTS as i64));
+        }
+    } else if quiz.retry_decay && attempt > 1 {
+        amount /= attempt as u128;
+        modifiers.push(("retry_decay".to_owned(), BASIS_POINTS as i64 / attempt as i64 - BASIS_POINTS as i64));
+    }
+
+    let credit_bps = context.credit_bps as u128;
+    if credit_bps != BASIS_POINTS {
+        amount = apply_bps(amount, credit_bps);
+        modifiers.push(("partial_credit".to_owned(), credit_bps as i64 - BASIS_POINTS as i64));
+    }
+
+    if context.hint_penalty_bps > 0 {
+        amount = apply_bps(amount, BASIS_POINTS - context.hint_penalty_bps as u128);
+        modifiers.push(("hint_penalty".to_owned(), -(context.hint_penalty_bps as i64)));
+    }
+
+    if let Some(cap) = context.account_cap.filter(|&cap| amount > cap) {
+        let kept_bps = cap.checked_mul(BASIS_POINTS).map_or_else(|| cap / (amount / BASIS_POINTS), |scaled| scaled / amount);
+        amount = cap;
+        modifiers.push(("account_cap".to_owned(), kept_bps as i64 - BASIS_POINTS as i64));
+    }
+
+    if let Some(pool) = context.pool.filter(|&pool| amount > pool) {
+        let kept_bps = pool.checked_mul(BASIS_POINTS).map_or_else(|| pool / (amount / BASIS_POINTS), |scaled| scaled / amount);
+        amount = pool;
+        modifiers.push(("pool_cap".to_owned(), kept_bps as i64 - BASIS_POINTS as i64));
+    }
+
+    let treasury_fee = apply_bps(amount, context.treasury_fee_bps as u128);
+
+    PayoutBreakdown {
+        base_prize: quiz.base_prize,
+        modifiers,
+        final_amount: amount,
+        treasury_fee,
+        solver_amount: amount - treasury_fee
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    const ONE_NEAR: u128 = 1_000_000_000_000_000_000_000_000;
+
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, bound: u128) -> u128 {
+            ((self.next() as u128) << 64 | self.next() as u128) % bound
+        }
+    }
+
+    struct Case {
+        base_prize: u128,
+        stage_payout_bps: Vec<u32>,
+        stale_policy: Option<StalePolicy>,
+        retry_decay: bool,
+        payout_schedule: Vec<u32>,
+        unsolved_sec: u64,
+        revealed_stage: usize,
+        treasury_fee_bps: u16,
+        difficulty_bps: u32,
+        streak_bps: u32,
+        credit_bps: u32,
+        hint_penalty_bps: u32,
+        time_decay_bps: u32,
+        account_cap: Option<u128>,
+        pool: Option<u128>
+    }
+
+    impl Case {
+        // No modifiers apply, so a single field can be changed to test one in isolation.
+        fn plain(base_prize: u128) -> Self {
+            Case {
+                base_prize,
+                stage_payout_bps: vec![],
+                stale_policy: None,
+                retry_decay: true,
+                payout_schedule: vec![],
+                unsolved_sec: 0,
+                revealed_stage: 0,
+                treasury_fee_bps: 0,
+                difficulty_bps: 10_000,
+                streak_bps: 10_000,
+                credit_bps: 10_000,
+                hint_penalty_bps: 0,
+                time_decay_bps: 10_000,
+                account_cap: None,
+                pool: None
+            }
+        }
+
+        fn payout(&self, attempt: u8) -> PayoutBreakdown {
+            let params = PayoutParams {
+                base_prize: self.base_prize,
+                stage_payout_bps: &self.stage_payout_bps,
+                stale_policy: self.stale_policy.as_ref(),
+                retry_decay: self.retry_decay,
+                payout_schedule: &self.payout_schedule
+            };
+            let context = PayoutContext {
+                revealed_stage: self.revealed_stage,
+                unsolved_sec: self.unsolved_sec,
+                treasury_fee_bps: self.treasury_fee_bps,
+                difficulty_bps: self.difficulty_bps,
+                streak_bps: self.streak_bps,
+                credit_bps: self.credit_bps,
+                hint_penalty_bps: self.hint_penalty_bps,
+                time_decay_bps: self.time_decay_bps,
+                account_cap: self.account_cap,
+                pool: self.pool
+            };
+            compute_payout(&params, attempt, &context)
+        }
+    }
+
+    fn cases() -> Vec<Case> {
+        let mut rng = Rng(0x5eed_cafe_f00d_d00d);
+        (0..500).map(|_| {
+            let parts = rng.below(4) as usize;
+            Case {
+                base_prize: rng.below(1_000_000 * ONE_NEAR),
+                stage_payout_bps: (0..parts).map(|_| rng.below(40_000) as u32).collect(),
+                stale_policy: if rng.below(2) == 0 { None } else {
+                    Some(StalePolicy {
+                        after_sec: rng.below(86_400) as u64 + 1,
+                        prize_increase_bps: rng.below(5_000) as u32,
+                        max_increases: rng.below(10) as u8
+                    })
+                },
+                retry_decay: rng.below(4) != 0,
+                payout_schedule: {
+                    let mut schedule: Vec<u32> = (0..rng.below(4)).map(|_| rng.below(10_001) as u32).collect();
+                    schedule.sort_unstable_by(|a, b| b.cmp(a));
+                    schedule
+                },
+                unsolved_sec: rng.below(30 * 86_400) as u64,
+                revealed_stage: rng.below(parts as u128 + 1) as usize,
+                treasury_fee_bps: rng.below(1_001) as u16,
+                pool: if rng.below(2) == 0 { None } else { Some(rng.below(2_000_000 * ONE_NEAR)) },
+                difficulty_bps: 5_000 + rng.below(25_000) as u32,
+                streak_bps: 10_000 + rng.below(10_000) as u32,
+                credit_bps: rng.below(10_001) as u32,
+                hint_penalty_bps: rng.below(10_001) as u32,
+                time_decay_bps: rng.below(10_001) as u32,
+                account_cap: if rng.below(2) == 0 { None } else { Some(rng.below(1_000_000 * ONE_NEAR)) }
+            }
+        }).collect()
+    }
+
+    #[test]
+    fn plain_prize_is_paid_in_full() {
+        let case = Case::plain(ONE_NEAR);
+        assert_eq!(case.payout(1), PayoutBreakdown {
+            base_prize: ONE_NEAR,
+            modifiers: vec![],
+            final_amount: ONE_NEAR,
+            treasury_fee: 0,
+            solver_amount: ONE_NEAR
+        });
+    }
+
+    #[test]
+    fn modifiers_are_listed_in_order() {
+        let case = Case { stage_payout_bps: vec![20_000], revealed_stage: 1, pool: Some(ONE_NEAR / 2), ..Case::plain(ONE_NEAR) };
+        let breakdown = case.payout(2);
+        assert_eq!(breakdown.modifiers, vec![
+            ("stage_multiplier".to_owned(), 10_000),
+            ("retry_decay".to_owned(), -5_000),
+            ("pool_cap".to_owned(), -5_000)
+        ]);
+        assert_eq!(breakdown.final_amount, ONE_NEAR / 2);
+    }
+
+    #[test]
+    fn never_exceeds_pool() {
+        for case in cases() {
+            for attempt in 1..=5 {
+                if let Some(pool) = case.pool {
+                    assert!(case.payout(attempt).final_amount <= pool);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn never_underflows() {
+        for case in cases() {
+            for attempt in 0..=u8::MAX {
+                let breakdown = case.payout(attempt);
+                assert!(breakdown.treasury_fee <= breakdown.final_amount);
+            }
+        }
+    }
+
+    #[test]
+    fn components_sum_to_final_amount() {
+        for case in cases() {
+            for attempt in 1..=5 {
+                let breakdown = case.payout(attempt);
+                assert_eq!(breakdown.solver_amount + breakdown.treasury_fee, breakdown.final_amount);
+            }
+        }
+    }
+
+    #[test]
+    fn decay_is_monotonic_in_attempt() {
+        for case in cases() {
+            let amounts: Vec<u128> = (1..=u8::MAX).map(|attempt| case.payout(attempt).final_amount).collect();
+            assert!(amounts.windows(2).all(|pair| pair[1] <= pair[0]));
+        }
+    }
+
+    const POLICY: StalePolicy = StalePolicy { after_sec: 3_600, prize_increase_bps: 1_000, max_increases: 3 };
+
+    #[test]
+    fn boost_before_first_interval() {
+        assert_eq!(boosted_prize(1_000, Some(&POLICY), 3_599, None), 1_000);
+        assert_eq!(boosted_prize(1_000, None, 1_000_000, None), 1_000);
+    }
+
+    #[test]
+    fn boost_after_one_interval() {
+        assert_eq!(boosted_prize(1_000, Some(&POLICY), 3_600, None), 1_100);
+    }
+
+    #[test]
+    fn boosts_compound_up_to_max_increases() {
+        assert_eq!(boosted_prize(1_000, Some(&POLICY), 3 * 3_600, None), 1_331);
+        assert_eq!(boosted_prize(1_000, Some(&POLICY), 100 * 3_600, None), 1_331);
+    }
+
+    #[test]
+    fn boost_respects_ceiling() {
+        assert_eq!(boosted_prize(1_000, Some(&POLICY), 3 * 3_600, Some(1_200)), 1_200);
+        assert_eq!(boosted_prize(1_000, Some(&POLICY), 3 * 3_600, Some(500)), 1_000);
+    }
+
+    #[test]
+    fn boost_is_listed_as_modifier() {
+        let case = Case { stale_policy: Some(POLICY), unsolved_sec: 3_600, ..Case::plain(1_000) };
+        let breakdown = case.payout(1);
+        assert_eq!(breakdown.modifiers, vec![("stale_boost".to_owned(), 1_000)]);
+        assert_eq!(breakdown.final_amount, 1_100);
+    }
+
+    #[test]
+    fn attempt_is_ignored_without_retry_decay() {
+        let case = Case { retry_decay: false, ..Case::plain(1_000) };
+        assert_eq!(case.payout(3), case.payout(1));
+    }
+
+    #[test]
+    fn difficulty_scales_before_stage_multiplier() {
+        let case = Case { stage_payout_bps: vec![5_000], revealed_stage: 1, difficulty_bps: 20_000, ..Case::plain(1_000) };
+        let breakdown = case.payout(1);
+        assert_eq!(breakdown.modifiers, vec![
+            ("difficulty_multiplier".to_owned(), 10_000),
+            ("stage_multiplier".to_owned(), -5_000)
+        ]);
+        assert_eq!(breakdown.final_amount, 1_000);
+    }
+
+    const DECAY: TimeDecay = TimeDecay { floor_bps: 2_000, step_sec: None };
+
+    #[test]
+    fn decay_is_linear_down_to_floor() {
+        assert_eq!(decay_bps(Some(&DECAY), 0, 1_000), 10_000);
+        assert_eq!(decay_bps(Some(&DECAY), 500, 1_000), 6_000);
+        assert_eq!(decay_bps(Some(&DECAY), 5_000, 1_000), 2_000);
+        assert_eq!(decay_bps(None, 500, 1_000), 10_000);
+    }
+
+    #[test]
+    fn stepped_decay_drops_at_step_boundaries() {
+        let decay = TimeDecay { step_sec: Some(250), ..DECAY };
+        assert_eq!(decay_bps(Some(&decay), 249, 1_000), 10_000);
+        assert_eq!(decay_bps(Some(&decay), 499, 1_000), 8_000);
+    }
+
+    #[test]
+    fn decay_is_listed_as_modifier() {
+        let case = Case { time_decay_bps: 6_000, ..Case::plain(1_000) };
+        let breakdown = case.payout(1);
+        assert_eq!(breakdown.modifiers, vec![("time_decay".to_owned(), -4_000)]);
+        assert_eq!(breakdown.final_amount, 600);
+    }
+
+    #[test]
+    fn payout_schedule_replaces_retry_decay() {
+        let case = Case { payout_schedule: vec![10_000, 7_500, 2_000], ..Case::plain(1_000) };
+        assert_eq!(case.payout(1).modifiers, vec![]);
+        assert_eq!(case.payout(2).final_amount, 750);
+        assert_eq!(case.payout(3).modifiers, vec![("payout_schedule".to_owned(), -8_000)]);
+        assert_eq!(case.payout(9).final_amount, 200);
+    }
+}
\ No newline at end of file
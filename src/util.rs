@@ -0,0 +1,208 @@
+use ed25519_dalek::{PublicKey, Signature};
+use near_sdk::borsh::BorshSerialize;
+use near_sdk::AccountId;
+use blake2::digest::consts::U32;
+use blake2::Blake2b;
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+
+use crate::{HashAlgo, QuizId};
+
+const NEAR_DECIMALS: usize = 24;
+
+pub fn hash_answer(answer: &str) -> String {
+    hash_with(HashAlgo::Sha256, answer)
+}
+
+pub fn hash_with(algo: HashAlgo, answer: &str) -> String {
+    match algo {
+        HashAlgo::Sha256 => format!("{:x}", Sha256::digest(answer.as_bytes())),
+        HashAlgo::Keccak256 => format!("{:x}", Keccak256::digest(answer.as_bytes())),
+        HashAlgo::Blake2b => format!("{:x}", Blake2b::<U32>::digest(answer.as_bytes()))
+    }
+}
+
+// Quizzes created without a salt use the empty one, which leaves their hashes plain.
+pub fn salted_hash(algo: HashAlgo, salt: &str, answer: &str) -> String {
+    hash_with(algo, &format!("{}{}", salt, answer))
+}
+
+// Normalized quizzes store the hash of this form, so clients have to normalize before hashing too.
+pub fn normalize_answer(answer: &str) -> String {
+    answer.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+// The salt keeps one quiz's option hashes from matching another's. It is stored with the quiz, so
+// it does nothing to stop the index being guessed: multiple-choice answers are public.
+pub fn choice_answer(salt: &str, option_index: u32) -> String {
+    format!("{}:{}", salt, option_index)
+}
+
+// Commitments cover the raw answer; normalization only happens once it is revealed.
+// Binding the account and quiz keeps a copied commitment from being revealed by anyone else or elsewhere.
+pub fn answer_commitment(account_id: &AccountId, quiz_id: QuizId, answer: &str, salt: &str) -> String {
+    let preimage = (account_id.as_str(), quiz_id, answer, salt).try_to_vec().unwrap();
+    format!("{:x}", Sha256::digest(preimage))
+}
+
+pub fn first_char_hash(answer: &str) -> String {
+    hash_answer(&answer.chars().take(1).collect::<String>())
+}
+
+pub fn signed_answer_message(contract_id: &AccountId, quiz_id: QuizId, answer: &str, nonce: u64) -> Vec<u8> {
+    (contract_id.as_str(), quiz_id, hash_answer(answer), nonce).try_to_vec().unwrap()
+}
+
+// An implicit account is named after its ed25519 key, so it can sign before it has ever sent a transaction.
+pub fn implicit_account_key(account_id: &AccountId) -> Option<Vec<u8>> {
+    let id = account_id.as_str();
+    if id.len() != 64 || !id.bytes().all(|byte| matches!(byte, b'0'..=b'9' | b'a'..=b'f')) {
+        return None;
+    }
+
+    (0..id.len()).step_by(2).map(|index| u8::from_str_radix(&id[index..index + 2], 16).ok()).collect()
+}
+
+pub fn verify_ed25519(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    let (Ok(public_key), Ok(signature)) = (PublicKey::from_bytes(public_key), Signature::try_from(signature)) else {
+        return false;
+    };
+
+    public_key.verify_strict(message, &signature).is_ok()
+}
+
+pub fn format_near(yocto: u128) -> String {
+    format_units(yocto, NEAR_DECIMALS)
+}
+
+pub fn parse_near(near: &str) -> Result<u128, String> {
+    parse_units(near, NEAR_DECIMALS)
+}
+
+fn unit_size(decimals: usize) -> Option<u128> {
+    u32::try_from(decimals).ok().and_then(|decimals| 10u128.checked_pow(decimals))
+}
+
+// format_near and parse_near for a token with the given number of decimals, e.g. 6 for USDC.
+pub fn format_units(amount: u128, decimals: usize) -> String {
+    // Past 38 decimals a whole unit is larger than any u128 amount.
+    let (whole, fraction) = match unit_size(decimals) {
+        Some(divisor) => (amount / divisor, amount % divisor),
+        None => (0, amount)
+    };
+
+    if fraction == 0 {
+        return whole.to_string();
+    }
+
+    let fraction = format!("{:0width$}", fraction, width = decimals);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
+pub fn parse_units(amount: &str, decimals: usize) -> Result<u128, String> {
+    let unit = unit_size(decimals).ok_or_else(|| format!("{} decimals do not fit in a u128 amount", decimals))?;
+    let (whole, fraction) = match amount.split_once('.') {
+        Some((whole, fraction)) => (whole, fraction),
+        None => (amount, "")
+    };
+
+    let is_digits = |part: &str| part.bytes().all(|byte| byte.is_ascii_digit());
+    if whole.is_empty() || !is_digits(whole) || !is_digits(fraction) || (amount.contains('.') && fraction.is_empty()) {
+        return Err(format!("\"{}\" is not a decimal amount", amount));
+    }
+    if fraction.len() > decimals {
+        return Err(format!("\"{}\" has more than {} fractional digits", amount, decimals));
+    }
+
+    let overflow = || format!("\"{}\" is too large", amount);
+    let whole = whole.parse::<u128>().map_err(|_| overflow())?;
+    let fraction = format!("{:0<width$}", fraction, width = decimals).parse::<u128>().unwrap_or(0);
+
+    whole
+        .checked_mul(unit)
+        .and_then(|units| units.checked_add(fraction))
+        .ok_or_else(overflow)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    const VECTORS: &[(&str, u128)] = &[
+        ("0", 0),
+        ("1", 1_000_000_000_000_000_000_000_000),
+        ("0.5", 500_000_000_000_000_000_000_000),
+        ("1.5", 1_500_000_000_000_000_000_000_000),
+        ("0.000000000000000000000001", 1),
+        ("123.456", 123_456_000_000_000_000_000_000_000),
+    ];
+
+    #[test]
+    fn hash_answer_is_hex_sha256() {
+        assert_eq!(hash_answer("Paris"), "5dd272b4f316b776a7b8e3d0894b37e1e42be3d5d3b204b8a5836cc50597a6b1");
+    }
+
+    #[test]
+    fn hash_with_known_digests() {
+        assert_eq!(hash_with(HashAlgo::Keccak256, ""), "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470");
+        assert_eq!(hash_with(HashAlgo::Blake2b, ""), "0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8");
+    }
+
+    #[test]
+    fn normalize_answer_trims_lowercases_and_collapses() {
+        assert_eq!(normalize_answer("  New\tYork   City "), "new york city");
+        assert_eq!(normalize_answer("Paris"), "paris");
+    }
+
+    #[test]
+    fn first_char_hash_hashes_leading_char() {
+        assert_eq!(first_char_hash("Paris"), hash_answer("P"));
+        assert_eq!(first_char_hash(""), hash_answer(""));
+    }
+
+    #[test]
+    fn format_vectors() {
+        for (near, yocto) in VECTORS {
+            assert_eq!(format_near(*yocto), *near);
+        }
+    }
+
+    #[test]
+    fn parse_vectors() {
+        for (near, yocto) in VECTORS {
+            assert_eq!(parse_near(near), Ok(*yocto));
+        }
+    }
+
+    #[test]
+    fn parse_trailing_zeros() {
+        assert_eq!(parse_near("1.500"), Ok(1_500_000_000_000_000_000_000_000));
+    }
+
+    #[test]
+    fn parse_too_many_decimals() {
+        assert!(parse_near("0.0000000000000000000000001").is_err());
+    }
+
+    #[test]
+    fn parse_malformed() {
+        for near in ["", ".5", "1.", "1.2.3", "-1", "1e3", " 1", "one"] {
+            assert!(parse_near(near).is_err(), "{:?} should not parse", near);
+        }
+    }
+
+    #[test]
+    fn parse_overflow() {
+        assert!(parse_near("400000000000000").is_err());
+    }
+
+    #[test]
+    fn units_with_token_decimals() {
+        assert_eq!(parse_units("25.5", 6), Ok(25_500_000));
+        assert_eq!(format_units(25_500_000, 6), "25.5");
+        assert_eq!(parse_units("7", 0), Ok(7));
+        assert!(parse_units("0.0000001", 6).is_err());
+        assert!(parse_units("1", 39).is_err());
+        assert_eq!(format_units(25, 39), "0.000000000000000000000000000000000000025");
+    }
+}